@@ -0,0 +1,436 @@
+use std::io::{self, stdin, stdout, Write};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use colored::Colorize;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+use crate::date_handler::today;
+use crate::display::{arrange_games, cleanup_terminal, print_game_detail, print_help, print_scoreboard, DisplayOptions,
+                     RawModeWriter};
+use crate::events::{diff_games, Leaders};
+use crate::game::Game;
+use crate::hooks::Hooks;
+use crate::html_parser::ParseError;
+use crate::keys::{Action, KeyBindings};
+use crate::provider::{ProviderError, ScoreProvider};
+use crate::refresh::{RefreshPolicy, RETRY_INTERVAL};
+use crate::sort::SortOrder;
+
+/// How long the events of a refresh stay on the screen
+const BANNER_DURATION: Duration = Duration::from_secs(60);
+
+/// What the live view is showing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    /// Every game of the day, with a cursor on the selected one
+    Scoreboard,
+    /// The detail page of the selected game
+    Detail,
+}
+
+/// What has to happen after a key press
+enum KeyOutcome {
+    /// Nothing changed
+    Ignore,
+    /// Only the screen has to be drawn again
+    Redraw,
+    /// The scoreboard has to be fetched right away (the day changed, or a refresh was asked for)
+    Fetch,
+    /// End the program
+    Quit,
+}
+
+/// The result of a background fetch, with the date it was for
+type FetchResult = (NaiveDate, Result<Vec<Result<Game, ParseError>>, ProviderError>);
+
+/// State of the live scoreboard
+pub struct App {
+    provider: Arc<dyn ScoreProvider>,
+    options: DisplayOptions,
+    refresh_policy: RefreshPolicy,
+    keys: KeyBindings,
+    date: NaiveDate,
+    // None until the first scoreboard of the day arrives
+    games: Option<Vec<Result<Game, ParseError>>>,
+    // why the last fetch failed, shown under the scoreboard
+    fetch_error: Option<String>,
+    // the game under the cursor and whether its detail page is open
+    selected: usize,
+    view: View,
+    // only the games being played are shown
+    live_only: bool,
+    // only the games of favorite teams are shown
+    favorites_only: bool,
+    // the help is drawn over the current view
+    show_help: bool,
+    // a fetch is running in the background
+    fetching: bool,
+    // when the scoreboard is fetched again, None if it isn't
+    next_refresh: Option<Instant>,
+    // what happened in the games at the last refresh that had anything happen, and until when it is
+    // shown
+    banner: Option<(String, Instant)>,
+    // the team last ahead in each game of the day, to see lead changes through a tie
+    leaders: Leaders,
+    // ring the terminal bell when something happens in a game
+    bell: bool,
+    // commands run when something happens in a game, and why the last one that failed did
+    hooks: Hooks,
+    hook_error: Option<String>,
+}
+
+impl App {
+    pub fn new(provider: Arc<dyn ScoreProvider>, date: NaiveDate, options: DisplayOptions,
+               refresh_policy: RefreshPolicy, keys: KeyBindings, bell: bool, hooks: Hooks) -> App {
+        App {
+            provider,
+            options,
+            refresh_policy,
+            keys,
+            date,
+            games: None,
+            fetch_error: None,
+            selected: 0,
+            view: View::Scoreboard,
+            live_only: false,
+            favorites_only: false,
+            show_help: false,
+            fetching: false,
+            next_refresh: None,
+            banner: None,
+            leaders: Leaders::new(),
+            bell,
+            hooks,
+            hook_error: None,
+        }
+    }
+
+    /// Runs the live scoreboard until the user quits. Key presses, the refresh timer, terminal
+    /// resizes and finished fetches are all handled as they happen; fetching is done in the
+    /// background so the screen keeps responding to keys meanwhile. A clock ticking every second
+    /// keeps the countdown to the next refresh up to date.
+    pub async fn run(mut self) -> io::Result<()> {
+        // raw mode means no stdin will print to the terminal
+        let raw_stdout = stdout().into_raw_mode()?;
+        let mut out = RawModeWriter::new(raw_stdout);
+
+        let mut keys = spawn_key_reader();
+        let mut resizes = signal(SignalKind::window_change())?;
+        let (fetch_sender, mut fetched) = mpsc::unbounded_channel::<FetchResult>();
+        let (hook_failures, mut failed_hooks) = mpsc::unbounded_channel::<String>();
+        let mut countdown = tokio::time::interval(Duration::from_secs(1));
+
+        self.fetch(&fetch_sender);
+        self.render(&mut out)?;
+        loop {
+            // only waited on when a refresh is planned
+            let refresh_at = self.next_refresh.unwrap_or_else(Instant::now);
+            tokio::select! {
+                key = keys.recv() => {
+                    // no more keys means stdin was closed
+                    let Some(key) = key else { break };
+                    match self.handle_key(key) {
+                        KeyOutcome::Ignore => {}
+                        KeyOutcome::Redraw => self.render(&mut out)?,
+                        KeyOutcome::Fetch => {
+                            self.fetch(&fetch_sender);
+                            self.render(&mut out)?;
+                        }
+                        KeyOutcome::Quit => break,
+                    }
+                }
+                _ = tokio::time::sleep_until(refresh_at), if self.next_refresh.is_some() => {
+                    self.fetch(&fetch_sender);
+                    self.render(&mut out)?;
+                }
+                _ = countdown.tick(), if self.next_refresh.is_some() => self.render(&mut out)?,
+                Some(_) = resizes.recv() => self.render(&mut out)?,
+                Some(failure) = failed_hooks.recv() => {
+                    self.hook_error = Some(failure);
+                    self.render(&mut out)?;
+                }
+                Some((date, result)) = fetched.recv() => {
+                    // the day was changed while this was being fetched, the fetch of the new day is
+                    // still running
+                    if date != self.date {
+                        continue;
+                    }
+                    self.fetching = false;
+                    match result {
+                        Ok(games) => {
+                            self.next_refresh = self
+                                .refresh_policy
+                                .next_refresh(date, &games)
+                                .map(|delay| Instant::now() + delay);
+                            // nothing happened yet on the first scoreboard of a day
+                            if let Some(previous) = &self.games {
+                                let events = diff_games(previous, &games, &mut self.leaders);
+                                // the hooks run in the background, failures come back on
+                                // `failed_hooks`
+                                self.hooks.run(date, &events, &hook_failures);
+                                if !events.is_empty() {
+                                    let messages: Vec<String> = events.iter().map(|event| event.message()).collect();
+                                    self.banner = Some((messages.join("   "), Instant::now() + BANNER_DURATION));
+                                    if self.bell {
+                                        out.write_all(b"\x07")?;
+                                    }
+                                }
+                            }
+                            // games can move when they are sorted by how they stand, the cursor
+                            // follows its game
+                            let selected = self.selected_matchup();
+                            self.games = Some(games);
+                            self.fetch_error = None;
+                            self.select_matchup(selected);
+                        }
+                        // keep showing the last scoreboard we got
+                        Err(err) => {
+                            self.fetch_error = Some(err.to_string());
+                            self.next_refresh = Some(Instant::now() + RETRY_INTERVAL);
+                        }
+                    }
+                    self.render(&mut out)?;
+                }
+            }
+        }
+
+        // leave raw mode before cleaning up
+        drop(out);
+        cleanup_terminal();
+        return Ok(());
+    }
+
+    /// Fetches the scoreboard of the current day in the background, the result arrives on `sender`
+    fn fetch(&mut self, sender: &mpsc::UnboundedSender<FetchResult>) {
+        self.fetching = true;
+        // the next refresh is planned once this fetch is done
+        self.next_refresh = None;
+        let provider = self.provider.clone();
+        let sender = sender.clone();
+        let date = self.date;
+        tokio::spawn(async move {
+            let result = provider.fetch_scoreboard(date).await;
+            // nothing to do with the scoreboard if the app quit while it was loading
+            let _ = sender.send((date, result));
+        });
+    }
+
+    /// Moves to another day, its scoreboard has to be fetched
+    fn change_date(&mut self, date: NaiveDate) -> KeyOutcome {
+        self.date = date;
+        self.games = None;
+        self.banner = None;
+        self.leaders.clear();
+        self.fetch_error = None;
+        self.selected = 0;
+        self.view = View::Scoreboard;
+        return KeyOutcome::Fetch;
+    }
+
+    /// Carries out the action bound to a key, see `KeyBindings` for which key does what. While the
+    /// help is open any key other than the one to quit closes it.
+    fn handle_key(&mut self, key: Key) -> KeyOutcome {
+        let action = self.keys.action(key);
+        if self.show_help && action != Some(Action::Quit) {
+            self.show_help = false;
+            return KeyOutcome::Redraw;
+        }
+        let Some(action) = action else { return KeyOutcome::Ignore };
+        let n_games = self.visible_games().map_or(0, |games| games.len());
+        match action {
+            // clean up and end program
+            Action::Quit => KeyOutcome::Quit,
+            // don't stack fetches up behind a slow site
+            Action::Refresh if self.fetching => KeyOutcome::Ignore,
+            Action::Refresh => KeyOutcome::Fetch,
+            // changing the day re-fetches right away instead of waiting for the next refresh
+            Action::PreviousDay => self.change_date(self.date.pred_opt().unwrap_or(self.date)),
+            Action::NextDay => self.change_date(self.date.succ_opt().unwrap_or(self.date)),
+            Action::Today => self.change_date(today()),
+            // move the cursor over the games of the scoreboard
+            Action::Up if self.view == View::Scoreboard => {
+                self.selected = self.selected.saturating_sub(1);
+                KeyOutcome::Redraw
+            }
+            Action::Down if self.view == View::Scoreboard => {
+                self.selected = (self.selected + 1).min(n_games.saturating_sub(1));
+                KeyOutcome::Redraw
+            }
+            Action::Up | Action::Down => KeyOutcome::Ignore,
+            // open and close the detail page of the selected game, there's none to open when no
+            // game is selected or it could not be read
+            Action::Open if self.selected_matchup().is_some() => {
+                self.view = View::Detail;
+                KeyOutcome::Redraw
+            }
+            Action::Open => KeyOutcome::Ignore,
+            Action::Back => {
+                self.view = View::Scoreboard;
+                KeyOutcome::Redraw
+            }
+            // show or hide parts of the scoreboard right away
+            Action::ToggleLeaders => {
+                self.options.leaders = !self.options.leaders;
+                KeyOutcome::Redraw
+            }
+            Action::ToggleLinescore => {
+                self.options.linescore = !self.options.linescore;
+                KeyOutcome::Redraw
+            }
+            Action::Filter => {
+                self.live_only = !self.live_only;
+                self.selected = 0;
+                self.view = View::Scoreboard;
+                KeyOutcome::Redraw
+            }
+            Action::FavoritesOnly => {
+                self.favorites_only = !self.favorites_only;
+                self.selected = 0;
+                self.view = View::Scoreboard;
+                KeyOutcome::Redraw
+            }
+            Action::Sort => {
+                let selected = self.selected_matchup();
+                self.options.sort = self.options.sort.next();
+                self.select_matchup(selected);
+                KeyOutcome::Redraw
+            }
+            Action::Help => {
+                self.show_help = true;
+                KeyOutcome::Redraw
+            }
+        }
+    }
+
+    /// The games shown on the scoreboard, favorites first, None until the first scoreboard of the
+    /// day arrives. Games that could not be read are shown unless only favorites are, they could
+    /// be in any state.
+    fn visible_games(&self) -> Option<Vec<&Result<Game, ParseError>>> {
+        let games = self.games.as_ref()?;
+        return Some(
+            arrange_games(games, &self.options)
+                .into_iter()
+                .filter(|game| !self.favorites_only || self.options.is_favorite(game))
+                .filter(|game| match game {
+                    Ok(game) if self.live_only => game.status.is_live(),
+                    _ => true,
+                })
+                .collect(),
+        );
+    }
+
+    /// The away and home team names of the game under the cursor, if it could be read
+    fn selected_matchup(&self) -> Option<(String, String)> {
+        let games = self.visible_games()?;
+        let game = games.get(self.selected)?.as_ref().ok()?;
+        return Some((game.away_team.name.clone(), game.home_team.name.clone()));
+    }
+
+    /// Puts the cursor back on the game between the given teams. If the game is gone (or wasn't
+    /// given) the cursor stays where it is, on the last game if there are fewer games now.
+    fn select_matchup(&mut self, matchup: Option<(String, String)>) {
+        let Some(games) = self.visible_games() else { return };
+        let position = matchup.and_then(|(away, home)| {
+            games.iter().position(|game| match game {
+                Ok(game) => game.away_team.name == away && game.home_team.name == home,
+                Err(_) => false,
+            })
+        });
+        self.selected = position.unwrap_or(self.selected.min(games.len().saturating_sub(1)));
+    }
+
+    /// Draws the current view, followed by the refresh footer. The detail view falls back on the
+    /// scoreboard if the selected game can't be shown (it could not be read).
+    ///
+    /// The screen is drawn into a buffer first and written out at once, so redrawing it every
+    /// second doesn't flicker.
+    fn render(&self, terminal: &mut dyn Write) -> io::Result<()> {
+        let mut buffer: Vec<u8> = Vec::new();
+        let out: &mut dyn Write = &mut buffer;
+        if self.show_help {
+            print_help(out, &self.keys.help())?;
+            terminal.write_all(&buffer)?;
+            return terminal.flush();
+        }
+
+        let games = self.visible_games();
+        let detail_game = match (self.view, &games) {
+            (View::Detail, Some(games)) => games.get(self.selected).and_then(|game| game.as_ref().ok()),
+            _ => None,
+        };
+        match detail_game {
+            Some(game) => print_game_detail(out, self.date, game)?,
+            None => print_scoreboard(out, self.date, games.as_deref(), &self.options, Some(self.selected))?,
+        }
+        if let Some((banner, until)) = &self.banner {
+            if Instant::now() < *until {
+                writeln!(out, "\n{}", format!(" {} ", banner).bold().reversed())?;
+            }
+        }
+        if let Some(err) = &self.fetch_error {
+            writeln!(out, "\n{}", err)?;
+        }
+        if let Some(failure) = &self.hook_error {
+            writeln!(out, "\n{}", failure.red())?;
+        }
+        writeln!(out, "\n{}", self.refresh_footer().dimmed())?;
+        if self.live_only {
+            writeln!(out, "{}", "Showing only the games being played".dimmed())?;
+        }
+        if self.options.sort != SortOrder::default() {
+            writeln!(out, "{}", format!("Sorted by {}", self.options.sort.description()).dimmed())?;
+        }
+        if self.favorites_only && self.options.favorites.is_empty() {
+            writeln!(out, "{}", "No favorite teams, list them under favorites in the config file".dimmed())?;
+        } else if self.favorites_only {
+            writeln!(out, "{}", "Showing only the games of favorite teams".dimmed())?;
+        }
+
+        terminal.write_all(&buffer)?;
+        return terminal.flush();
+    }
+
+    /// Tells when the scoreboard is fetched again
+    fn refresh_footer(&self) -> String {
+        if self.fetching {
+            return String::from("Refreshing...");
+        }
+        match self.next_refresh {
+            Some(next_refresh) => {
+                let seconds = next_refresh.saturating_duration_since(Instant::now()).as_secs();
+                format!("Next refresh in {}", format_countdown(seconds))
+            }
+            None => String::from("Every game is over, not refreshing"),
+        }
+    }
+}
+
+/// Formats a number of seconds as "42s" or "9m 05s"
+fn format_countdown(seconds: u64) -> String {
+    if seconds < 60 {
+        return format!("{}s", seconds);
+    }
+    return format!("{}m {:02}s", seconds / 60, seconds % 60);
+}
+
+/// Reads key presses on a thread of its own (reading stdin blocks) and sends them to the returned
+/// channel
+fn spawn_key_reader() -> mpsc::UnboundedReceiver<Key> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for key in stdin().keys().flatten() {
+            // stop reading once the app has quit
+            if sender.send(key).is_err() {
+                break;
+            }
+        }
+    });
+    return receiver;
+}
@@ -0,0 +1,277 @@
+use std::sync::OnceLock;
+
+use clap::ValueEnum;
+
+/// When to color the output
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when writing to a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+/// Decides whether the output is colored, following https://no-color.org: in auto mode a non empty
+/// NO_COLOR environment variable turns colors off, as does writing to something else than a
+/// terminal (a file, a pipe, CI logs).
+///
+/// # Arguments
+///
+/// * `choice` - What was asked for on the command line
+/// * `is_tty` - Whether the output is a terminal
+pub fn use_color(choice: ColorChoice, is_tty: bool) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            is_tty && !no_color
+        }
+    }
+}
+
+/// How many colors the terminal can show
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    /// Guess from COLORTERM and TERM
+    Auto,
+    /// 24-bit RGB colors
+    Truecolor,
+    /// The xterm 256 color palette
+    #[clap(name = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors
+    #[clap(name = "16")]
+    Ansi16,
+}
+
+// the depth the program is set to, detected on first use if never set
+static COLOR_DEPTH: OnceLock<ColorDepth> = OnceLock::new();
+
+/// The 16 basic ANSI colors as xterm shows them by default, in the order of their codes
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each component in the 6x6x6 color cube of the xterm 256 color palette
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Sets the color depth team colors are drawn with for the rest of the program. Auto detects it
+/// from the environment. Only the first call has any effect.
+pub fn set_color_depth(depth: ColorDepth) {
+    let depth = match depth {
+        ColorDepth::Auto => detect_color_depth(),
+        depth => depth,
+    };
+    let _ = COLOR_DEPTH.set(depth);
+}
+
+/// Guesses how many colors the terminal can show: COLORTERM=truecolor (or 24bit) is set by the
+/// terminals that support RGB colors, a TERM ending in 256color (xterm-256color, screen-256color,
+/// tmux-256color) has the 256 color palette, anything else is assumed to have the basic 16 colors.
+pub fn detect_color_depth() -> ColorDepth {
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default().to_lowercase();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorDepth::Truecolor;
+    }
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term.contains("256color") {
+        return ColorDepth::Ansi256;
+    }
+    return ColorDepth::Ansi16;
+}
+
+/// Writes `text` on a background of the given color, as close as the color depth allows
+pub fn paint_bg(text: &str, rgb: (u8, u8, u8)) -> String {
+    return paint(text, &color_code(rgb, true));
+}
+
+/// Writes `text` on a background of the given color, in black or white, whichever reads better on
+/// the color the terminal actually shows
+pub fn paint_badge(text: &str, background: (u8, u8, u8)) -> String {
+    let foreground = readable_foreground(shown_color(background));
+    return paint(text, &format!("{};{}", color_code(foreground, false), color_code(background, true)));
+}
+
+/// Picks black or white text for a background, whichever has the higher WCAG contrast ratio with it
+pub fn readable_foreground(background: (u8, u8, u8)) -> (u8, u8, u8) {
+    const BLACK: (u8, u8, u8) = (0, 0, 0);
+    const WHITE: (u8, u8, u8) = (255, 255, 255);
+    if contrast_ratio(background, WHITE) >= contrast_ratio(background, BLACK) {
+        return WHITE;
+    }
+    return BLACK;
+}
+
+/// The WCAG contrast ratio of two colors, from 1 (no contrast) to 21 (black on white)
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let (lighter, darker) = {
+        let (a, b) = (relative_luminance(a), relative_luminance(b));
+        if a > b { (a, b) } else { (b, a) }
+    };
+    return (lighter + 0.05) / (darker + 0.05);
+}
+
+/// The WCAG relative luminance of an sRGB color, from 0 (black) to 1 (white)
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |component: u8| {
+        let component = component as f64 / 255.0;
+        if component <= 0.03928 {
+            component / 12.92
+        } else {
+            ((component + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    return 0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b);
+}
+
+/// The color the terminal shows for an RGB color at the color depth the program is set to
+fn shown_color(rgb: (u8, u8, u8)) -> (u8, u8, u8) {
+    match COLOR_DEPTH.get_or_init(detect_color_depth) {
+        ColorDepth::Truecolor | ColorDepth::Auto => rgb,
+        ColorDepth::Ansi256 => ansi256_rgb(nearest_ansi256(rgb)),
+        ColorDepth::Ansi16 => ANSI16_PALETTE[nearest_ansi16(rgb)],
+    }
+}
+
+/// The SGR code of an RGB color at the color depth the program is set to, as a foreground or
+/// background color
+fn color_code((r, g, b): (u8, u8, u8), background: bool) -> String {
+    // extended colors, basic colors, bright basic colors
+    let (extended, basic, bright) = if background { (48, 40, 100) } else { (38, 30, 90) };
+    match COLOR_DEPTH.get_or_init(detect_color_depth) {
+        ColorDepth::Truecolor | ColorDepth::Auto => format!("{};2;{};{};{}", extended, r, g, b),
+        ColorDepth::Ansi256 => format!("{};5;{}", extended, nearest_ansi256((r, g, b))),
+        ColorDepth::Ansi16 => match nearest_ansi16((r, g, b)) {
+            index @ 0..=7 => (basic + index).to_string(),
+            index => (bright + index - 8).to_string(),
+        },
+    }
+}
+
+/// Wraps `text` in the escape codes of an SGR attribute, unless colors are turned off
+fn paint(text: &str, code: &str) -> String {
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        return String::from(text);
+    }
+    return format!("\x1b[{}m{}\x1b[0m", code, text);
+}
+
+/// Finds the color of the xterm 256 color palette closest to an RGB color, among the 6x6x6 color
+/// cube (16 to 231) and the grayscale ramp (232 to 255)
+pub fn nearest_ansi256(rgb: (u8, u8, u8)) -> u8 {
+    let cube_index = |value: u8| -> usize {
+        return (0..CUBE_LEVELS.len())
+            .min_by_key(|index| (CUBE_LEVELS[*index] as i32 - value as i32).abs())
+            .unwrap();
+    };
+    let (r, g, b) = (cube_index(rgb.0), cube_index(rgb.1), cube_index(rgb.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    // the gray ramp goes from 8 to 238 in steps of 10
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray_index = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_index;
+
+    if distance(rgb, (gray_level, gray_level, gray_level)) < distance(rgb, cube) {
+        return 232 + gray_index;
+    }
+    return 16 + (36 * r + 6 * g + b) as u8;
+}
+
+/// The RGB color of a color of the xterm 256 color palette
+fn ansi256_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI16_PALETTE[index as usize],
+        16..=231 => {
+            let cube = (index - 16) as usize;
+            (CUBE_LEVELS[cube / 36], CUBE_LEVELS[cube / 6 % 6], CUBE_LEVELS[cube % 6])
+        }
+        _ => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+    }
+}
+
+/// Finds the basic ANSI color (0 to 15) closest to an RGB color
+pub fn nearest_ansi16(rgb: (u8, u8, u8)) -> usize {
+    return (0..ANSI16_PALETTE.len())
+        .min_by_key(|index| distance(rgb, ANSI16_PALETTE[*index]))
+        .unwrap();
+}
+
+/// Squared euclidean distance between two RGB colors
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let component = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    return component(a.0, b.0) + component(a.1, b.1) + component(a.2, b.2);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_nearest_256_color() {
+        assert_eq!(nearest_ansi256((255, 0, 0)), 196);
+        assert_eq!(nearest_ansi256((0, 0, 0)), 16);
+        assert_eq!(nearest_ansi256((255, 255, 255)), 231);
+        // grays go to the gray ramp
+        assert_eq!(nearest_ansi256((128, 128, 128)), 244);
+        assert_eq!(nearest_ansi256((130, 127, 126)), 244);
+        // Lakers purple, between two levels of the cube
+        assert_eq!(nearest_ansi256((85, 37, 130)), 54);
+    }
+
+    #[test]
+    fn finds_every_256_color_it_draws() {
+        for index in 16..=255 {
+            assert_eq!(nearest_ansi256(ansi256_rgb(index)), index);
+        }
+    }
+
+    #[test]
+    fn finds_the_nearest_basic_color() {
+        assert_eq!(nearest_ansi16((0, 0, 0)), 0);
+        assert_eq!(nearest_ansi16((200, 10, 10)), 1);
+        assert_eq!(nearest_ansi16((255, 20, 20)), 9);
+        assert_eq!(nearest_ansi16((120, 120, 120)), 8);
+        assert_eq!(nearest_ansi16((250, 250, 250)), 15);
+        for (index, rgb) in ANSI16_PALETTE.iter().enumerate() {
+            assert_eq!(nearest_ansi16(*rgb), index);
+        }
+    }
+
+    #[test]
+    fn picks_readable_text() {
+        assert_eq!(readable_foreground((255, 255, 255)), (0, 0, 0));
+        assert_eq!(readable_foreground((0, 0, 0)), (255, 255, 255));
+        // Lakers gold, Celtics green, Lakers purple
+        assert_eq!(readable_foreground((253, 185, 39)), (0, 0, 0));
+        assert_eq!(readable_foreground((0, 122, 51)), (255, 255, 255));
+        assert_eq!(readable_foreground((85, 37, 130)), (255, 255, 255));
+    }
+
+    #[test]
+    fn measures_contrast_ratios() {
+        let ratio = contrast_ratio((255, 255, 255), (0, 0, 0));
+        assert!((ratio - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio((0, 0, 0), (255, 255, 255)) - ratio).abs() < 1e-9);
+        assert!((contrast_ratio((0, 122, 51), (0, 122, 51)) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::hooks::Hooks;
+use crate::keys::Action;
+use crate::teams::{find_team, TeamInfo};
+
+/// Errors for a config file that exists but can't be used
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The config file could not be read
+    Io(PathBuf, io::Error),
+    /// The config file is not valid TOML, or has settings the program doesn't know
+    Toml(PathBuf, toml::de::Error),
+    /// A key binding names a key the program doesn't know
+    UnknownKey(String),
+    /// The favorites name a team the program doesn't know
+    UnknownTeam(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "could not read config file {}: {}", path.display(), err),
+            ConfigError::Toml(path, err) => write!(f, "invalid config file {}: {}", path.display(), err),
+            ConfigError::UnknownKey(key) => write!(f, "unknown key '{}' in the [keys] of the config file", key),
+            ConfigError::UnknownTeam(team) => write!(f, "unknown team '{}' in the favorites of the config file", team),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Settings read from `config.toml` in the config directory. Everything is optional, a missing file
+/// is the same as an empty one.
+///
+/// ```toml
+/// favorites = ["LAL", "Boston"]
+///
+/// [keys]
+/// quit = ["q", "ctrl-c"]
+/// refresh = "r"
+///
+/// [hooks]
+/// on_final = "notify-send \"$NBA_MESSAGE\""
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Teams whose games are pinned to the top of the scoreboard, by any name `find_team` knows
+    pub favorites: Vec<String>,
+    /// Keys bound to each action, replacing the default keys of that action
+    pub keys: HashMap<Action, KeyList>,
+    /// Commands run when something happens in a game
+    pub hooks: Hooks,
+}
+
+/// One key or a list of keys
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn names(&self) -> &[String] {
+        match self {
+            KeyList::One(name) => std::slice::from_ref(name),
+            KeyList::Many(names) => names,
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file: `$XDG_CONFIG_HOME/nba-scores-cli/config.toml`, or under
+    /// `~/.config` if that is not set
+    pub fn path() -> Option<PathBuf> {
+        return Some(config_dir()?.join("config.toml"));
+    }
+
+    /// Reads the config file from its default location. Having no config file is the same as
+    /// having an empty one.
+    pub fn load() -> Result<Config, ConfigError> {
+        match Config::path() {
+            Some(path) if path.exists() => Config::load_from(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    /// The favorite teams, looked up in the team registry
+    pub fn favorite_teams(&self) -> Result<Vec<&'static TeamInfo>, ConfigError> {
+        return self
+            .favorites
+            .iter()
+            .map(|name| find_team(name).ok_or_else(|| ConfigError::UnknownTeam(name.clone())))
+            .collect();
+    }
+
+    /// Reads the config file at `path`
+    pub fn load_from(path: &Path) -> Result<Config, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+        return toml::from_str(&contents).map_err(|err| ConfigError::Toml(path.to_path_buf(), err));
+    }
+}
+
+/// Directory the program's files (config, season calendar) are kept in:
+/// `$XDG_CONFIG_HOME/nba-scores-cli`, or `~/.config/nba-scores-cli` if that is not set
+pub fn config_dir() -> Option<PathBuf> {
+    let config_home = match std::env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+    };
+    return Some(config_home.join("nba-scores-cli"));
+}
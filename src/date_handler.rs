@@ -1,46 +1,320 @@
+use std::fmt;
+use std::path::PathBuf;
 
-fn format_date(date: String) -> String {
-    // Given a String in the form YYYY-MM-DD, Returns String in the form YYYYMMDD
-    return date.split('-').collect::<Vec<&str>>()[0..3].join("");
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::config::config_dir;
+
+/// Errors for a date argument the program could not turn into a date
+#[derive(Debug)]
+pub enum DateError {
+    /// The argument is not in any of the recognized formats
+    Unrecognized(String),
+    /// The argument is in a recognized format, but is not a real date (e.g. 20261345)
+    InvalidDate(String),
+    /// The argument is not a known name in the season calendar, or the calendar can't be read
+    Calendar(String),
+    /// The start of a date range comes after its end
+    InvalidRange(NaiveDate, NaiveDate),
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::Unrecognized(date) => write!(
+                f,
+                "could not understand the date '{}'. The formats recognized are t, T, y, \
+                 YYYYMMDD, YYYY-MM-DD, MM/DD, -N/+N (days from today), weekdays (\"sat\", \
+                 \"last friday\", \"next mon\") and names from the season calendar",
+                date
+            ),
+            DateError::InvalidDate(date) => write!(f, "'{}' is not a valid date", date),
+            DateError::Calendar(message) => write!(f, "{}", message),
+            DateError::InvalidRange(from, to) => {
+                write!(f, "the date range starts ({}) after it ends ({})", from, to)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DateError {}
+
+/// Returns the current (local) date
+pub fn today() -> NaiveDate {
+    return chrono::offset::Local::now().date_naive();
 }
 
-// TODO: return Result??
-/// Parse date argument and return the given date in YYYYMMDD format
-pub fn extract_date_argument(date: &String) -> String {
+/// Parse date argument and return the date it stands for
+pub fn extract_date_argument(date: &str) -> Result<NaiveDate, DateError> {
     // retrieve current date -- chrono makes getting surrounding days EASY
-    let current_date = chrono::offset::Local::now().date();
+    return parse_date(date, today());
+}
+
+/// Parse the two ends of a date range (each in any format `extract_date_argument` accepts) and
+/// return every date of the range, both ends included
+pub fn extract_date_range(from: &str, to: &str) -> Result<Vec<NaiveDate>, DateError> {
+    let from = extract_date_argument(from)?;
+    let to = extract_date_argument(to)?;
+    if from > to {
+        return Err(DateError::InvalidRange(from, to));
+    }
+    return Ok(from.iter_days().take_while(|date| *date <= to).collect());
+}
 
-    match date.as_str() {
+/// Turns a date argument into a date, relative to `today`. Tries each recognized format in turn,
+/// and falls back on the named dates of the season calendar.
+fn parse_date(date: &str, today: NaiveDate) -> Result<NaiveDate, DateError> {
+    let date = date.trim();
+    let invalid = || DateError::InvalidDate(String::from(date));
+
+    match date {
         // check for shortcut arguments -- use current date
-        "t" => return format_date(current_date.to_string()),
-        "T" => return format_date(current_date.succ().to_string()),
-        "y" => return format_date(current_date.pred().to_string()),
-        // TODO: handle the different date formats here by passing them into conversion function
-        // If the string given is a usable date, panic!
-        _ => panic!(
-            "Please give a recognizable date format. The formats recognized are \
-                    YYYYMMDD, ..."
-        ),
+        "t" => return Ok(today),
+        "T" => return today.succ_opt().ok_or_else(invalid),
+        "y" => return today.pred_opt().ok_or_else(invalid),
+        _ => {}
+    }
+
+    // YYYYMMDD
+    if date.len() == 8 && date.chars().all(|c| c.is_ascii_digit()) {
+        return NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| invalid());
+    }
+    // YYYY-MM-DD
+    if date.len() == 10 && date.chars().nth(4) == Some('-') {
+        return NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| invalid());
+    }
+    // MM/DD
+    if let Some((month, day)) = date.split_once('/') {
+        let month = month.parse::<u32>().map_err(|_| invalid())?;
+        let day = day.parse::<u32>().map_err(|_| invalid())?;
+        return nearest_month_day(month, day, today).ok_or_else(invalid);
+    }
+    // -N / +N days from today
+    if date.starts_with('-') || date.starts_with('+') {
+        if let Ok(offset) = date.parse::<i64>() {
+            return today
+                .checked_add_signed(Duration::days(offset))
+                .ok_or_else(invalid);
+        }
+    }
+    // sat, last friday, next mon, ...
+    if let Some(weekday_date) = parse_weekday(date, today) {
+        return Ok(weekday_date);
+    }
+
+    return SeasonCalendar::load()?.lookup(date, today);
+}
+
+/// Returns the date of the given month and day closest to `today`. Seasons span two years, so
+/// "04/15" asked for in October is most likely last April.
+fn nearest_month_day(month: u32, day: u32, today: NaiveDate) -> Option<NaiveDate> {
+    return [today.year() - 1, today.year(), today.year() + 1]
+        .iter()
+        .filter_map(|year| NaiveDate::from_ymd_opt(*year, month, day))
+        .min_by_key(|candidate| (*candidate - today).num_days().abs());
+}
+
+/// Parses a weekday relative to `today`:
+/// * "sat" or "saturday" - the coming Saturday, today included
+/// * "next sat" - the coming Saturday, today excluded
+/// * "last sat" - the most recent Saturday before today
+fn parse_weekday(date: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let lowered = date.to_lowercase();
+    let words: Vec<&str> = lowered.split_whitespace().collect();
+    let (direction, weekday) = match words.as_slice() {
+        [weekday] => ("this", weekday),
+        [direction, weekday] => (*direction, weekday),
+        _ => return None,
     };
-    // TODO check if in usable format
-    //if !date_format_usable(&date) {
-    // TODO if not supplied with valid date -- panic!
-    // How do we decide what date formats we can use?
+    let weekday = weekday.parse::<Weekday>().ok()?;
+
+    let days_ahead = (weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64).rem_euclid(7);
+    let offset = match direction {
+        "this" => days_ahead,
+        "next" if days_ahead == 0 => 7,
+        "next" => days_ahead,
+        "last" if days_ahead == 0 => -7,
+        "last" => days_ahead - 7,
+        _ => return None,
+    };
+    return today.checked_add_signed(Duration::days(offset));
+}
+
+/// Named dates of NBA seasons ("opening-night", "christmas", "trade-deadline", ...) read from a
+/// plain text file in the config directory, one date per line:
+///
+/// ```text
+/// # name           date
+/// opening-night    2026-10-20
+/// christmas        2026-12-25
+/// ```
+///
+/// The same name can be listed for several seasons, the date closest to today is used.
+pub struct SeasonCalendar {
+    dates: Vec<(String, NaiveDate)>,
+}
+
+impl SeasonCalendar {
+    /// Location of the season calendar: `$XDG_CONFIG_HOME/nba-scores-cli/calendar.txt`, or under
+    /// `~/.config` if that is not set
+    pub fn path() -> Option<PathBuf> {
+        return Some(config_dir()?.join("calendar.txt"));
+    }
+
+    /// Reads the season calendar from its default location. Having no calendar is the same as
+    /// having an empty one.
+    pub fn load() -> Result<SeasonCalendar, DateError> {
+        let path = match SeasonCalendar::path() {
+            Some(path) if path.exists() => path,
+            _ => return Ok(SeasonCalendar { dates: Vec::new() }),
+        };
+        let contents = std::fs::read_to_string(&path).map_err(|err| {
+            DateError::Calendar(format!("could not read season calendar {}: {}", path.display(), err))
+        })?;
+        return SeasonCalendar::parse(&contents);
+    }
 
-    //}
+    /// Parses the contents of a season calendar file
+    pub fn parse(contents: &str) -> Result<SeasonCalendar, DateError> {
+        let mut dates: Vec<(String, NaiveDate)> = Vec::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, date) = line
+                .rsplit_once(char::is_whitespace)
+                .ok_or_else(|| DateError::Calendar(format!("malformed season calendar line '{}'", line)))?;
+            let date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+                .map_err(|_| DateError::Calendar(format!("malformed date in season calendar line '{}'", line)))?;
+            dates.push((normalize_name(name), date));
+        }
+        return Ok(SeasonCalendar { dates });
+    }
 
-    //println!("Could not understand date given.. showing Today's scores.");
-    //return extract_date_argument(&String::from("t"));
+    /// Finds the date of the given name closest to `today`
+    pub fn lookup(&self, name: &str, today: NaiveDate) -> Result<NaiveDate, DateError> {
+        let normalized = normalize_name(name);
+        return self
+            .dates
+            .iter()
+            .filter(|(calendar_name, _)| *calendar_name == normalized)
+            .map(|(_, date)| *date)
+            .min_by_key(|date| (*date - today).num_days().abs())
+            .ok_or_else(|| DateError::Unrecognized(String::from(name)));
+    }
 }
 
-// Should this be Result?
+/// "Opening Night", "opening_night" and "opening-night" all name the same date
+fn normalize_name(name: &str) -> String {
+    return name
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+}
 
-fn date_format_usable(date: &String) -> bool {
-    // trim off extra characters, if any
-    if date.len() != 8 {
-        return false;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // a Friday
+    const FRIDAY: (i32, u32, u32) = (2026, 10, 16);
+
+    fn friday() -> NaiveDate {
+        ymd(FRIDAY.0, FRIDAY.1, FRIDAY.2)
     }
-    println!("{}", &date);
 
-    return false;
+    #[test]
+    fn parses_shortcuts_across_the_year_boundary() {
+        let new_years_eve = ymd(2026, 12, 31);
+        assert_eq!(parse_date("t", new_years_eve).unwrap(), new_years_eve);
+        assert_eq!(parse_date("T", new_years_eve).unwrap(), ymd(2027, 1, 1));
+        assert_eq!(parse_date("y", ymd(2027, 1, 1)).unwrap(), new_years_eve);
+    }
+
+    #[test]
+    fn parses_full_dates() {
+        assert_eq!(parse_date("20261010", friday()).unwrap(), ymd(2026, 10, 10));
+        assert_eq!(parse_date("2026-10-10", friday()).unwrap(), ymd(2026, 10, 10));
+        assert!(matches!(parse_date("20261345", friday()), Err(DateError::InvalidDate(_))));
+        assert!(matches!(parse_date("2026-02-29", friday()), Err(DateError::InvalidDate(_))));
+    }
+
+    #[test]
+    fn parses_days_from_today() {
+        assert_eq!(parse_date("-3", friday()).unwrap(), ymd(2026, 10, 13));
+        assert_eq!(parse_date("+2", friday()).unwrap(), ymd(2026, 10, 18));
+        assert_eq!(parse_date("+0", friday()).unwrap(), friday());
+    }
+
+    #[test]
+    fn parses_month_day_closest_to_today() {
+        // across the year boundary, both ways
+        assert_eq!(parse_date("01/02", ymd(2026, 12, 30)).unwrap(), ymd(2027, 1, 2));
+        assert_eq!(parse_date("12/28", ymd(2027, 1, 3)).unwrap(), ymd(2026, 12, 28));
+        // early in the season, April is last April
+        assert_eq!(parse_date("04/15", ymd(2026, 10, 1)).unwrap(), ymd(2026, 4, 15));
+        assert_eq!(parse_date("10/20", friday()).unwrap(), ymd(2026, 10, 20));
+        // February 29th only exists in leap years, the closest one is used
+        assert_eq!(parse_date("02/29", ymd(2027, 10, 1)).unwrap(), ymd(2028, 2, 29));
+        assert!(matches!(parse_date("02/30", friday()), Err(DateError::InvalidDate(_))));
+        assert!(matches!(parse_date("ab/cd", friday()), Err(DateError::InvalidDate(_))));
+    }
+
+    #[test]
+    fn parses_weekdays_on_that_weekday() {
+        // today counts as "fri", not as "next fri" or "last fri"
+        assert_eq!(parse_weekday("fri", friday()), Some(friday()));
+        assert_eq!(parse_weekday("friday", friday()), Some(friday()));
+        assert_eq!(parse_weekday("next fri", friday()), Some(ymd(2026, 10, 23)));
+        assert_eq!(parse_weekday("last fri", friday()), Some(ymd(2026, 10, 9)));
+    }
+
+    #[test]
+    fn parses_weekdays_the_day_after() {
+        let saturday = ymd(2026, 10, 17);
+        assert_eq!(parse_weekday("fri", saturday), Some(ymd(2026, 10, 23)));
+        assert_eq!(parse_weekday("next fri", saturday), Some(ymd(2026, 10, 23)));
+        assert_eq!(parse_weekday("last fri", saturday), Some(friday()));
+        assert_eq!(parse_weekday("Last Saturday", saturday), Some(ymd(2026, 10, 10)));
+        assert_eq!(parse_weekday("sun", saturday), Some(ymd(2026, 10, 18)));
+    }
+
+    #[test]
+    fn rejects_other_words() {
+        assert_eq!(parse_weekday("previous fri", friday()), None);
+        assert_eq!(parse_weekday("last", friday()), None);
+        assert_eq!(parse_weekday("last fri night", friday()), None);
+        assert_eq!(parse_weekday("opening-night", friday()), None);
+    }
+
+    #[test]
+    fn looks_up_the_closest_calendar_date() {
+        let calendar = SeasonCalendar::parse(
+            "# name           date\n\
+             opening-night    2025-10-21\n\
+             opening night    2026-10-20\n\
+             \n\
+             christmas        2026-12-25\n",
+        )
+        .unwrap();
+        assert_eq!(calendar.lookup("Opening_Night", friday()).unwrap(), ymd(2026, 10, 20));
+        assert_eq!(calendar.lookup("christmas", friday()).unwrap(), ymd(2026, 12, 25));
+        assert!(matches!(calendar.lookup("trade-deadline", friday()), Err(DateError::Unrecognized(_))));
+        assert!(matches!(SeasonCalendar::parse("christmas"), Err(DateError::Calendar(_))));
+        assert!(matches!(SeasonCalendar::parse("christmas 12/25"), Err(DateError::Calendar(_))));
+    }
+
+    #[test]
+    fn rejects_backwards_ranges() {
+        assert!(matches!(extract_date_range("20261016", "20261010"), Err(DateError::InvalidRange(_, _))));
+        assert_eq!(extract_date_range("20261010", "20261012").unwrap().len(), 3);
+    }
 }
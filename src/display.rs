@@ -1,24 +1,290 @@
-use std::io::{Write, stdout};
-
-/// Prints a header for the program in the following format:
-///       Away             Home          Score       Status                                                                                                                                                             │
-///       ----             ----          -----       ------
-///
-pub fn print_header() {
-    println!(
-        "{}",
-        format!(
-            "{:^16} {:^16}{:^13}\t{:^9}",
-            "Away", "Home", "Score", "Status"
-        )
-    );
-    println!(
-        "{}",
-        format!(
-            "{:^16} {:^16}{:^13}\t{:^9}",
-            "----", "----", "-----", "------"
-        )
-    );
+use std::io::{self, Write, stdout};
+
+use chrono::NaiveDate;
+use colored::Colorize;
+
+use crate::game::Game;
+use crate::html_parser::ParseError;
+use crate::keys::Action;
+use crate::sort::SortOrder;
+use crate::teams::{NameStyle, TeamInfo};
+
+/// Controls what is shown for each game on the scoreboard
+#[derive(Debug, Default, Clone)]
+pub struct DisplayOptions {
+    /// Print the quarter by quarter line score under each game that has started
+    pub linescore: bool,
+    /// Print the points, rebounds and assists leaders of both teams under each game that has
+    /// started
+    pub leaders: bool,
+    /// How team names are written
+    pub name_style: NameStyle,
+    /// Teams whose games are marked with a star, and pinned to the top when sorting by favorites
+    pub favorites: Vec<&'static TeamInfo>,
+    /// The order games are listed in
+    pub sort: SortOrder,
+}
+
+impl DisplayOptions {
+    /// Whether one of the favorite teams plays in the game
+    pub fn is_favorite(&self, game: &Result<Game, ParseError>) -> bool {
+        match game {
+            Ok(game) => game.involves(&self.favorites),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Writer for a terminal in raw mode, where a newline no longer moves the cursor back to the start
+/// of the line: every "\n" written through it becomes "\r\n".
+///
+/// # Examples
+///
+/// ```
+/// let stdout = stdout().into_raw_mode()?;
+/// let mut out = RawModeWriter::new(stdout.lock());
+/// print_header(&mut out, &DisplayOptions::default())?;
+/// ```
+pub struct RawModeWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> RawModeWriter<W> {
+    pub fn new(inner: W) -> RawModeWriter<W> {
+        RawModeWriter { inner }
+    }
+}
+
+impl<W: Write> Write for RawModeWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut lines = buf.split(|byte| *byte == b'\n');
+        if let Some(first) = lines.next() {
+            self.inner.write_all(first)?;
+        }
+        for line in lines {
+            self.inner.write_all(b"\r\n")?;
+            self.inner.write_all(line)?;
+        }
+        return Ok(buf.len());
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Clears the terminal and prints the whole scoreboard of a day: the date and header followed by
+/// every game, or an error row in place of each game that could not be read. `games` is None while
+/// the scoreboard of the day is still being fetched.
+///
+/// # Examples
+///
+/// ```
+/// let games = provider.fetch_scoreboard(date).await?;
+/// let games: Vec<_> = games.iter().collect();
+/// print_scoreboard(&mut stdout(), date, Some(&games), &DisplayOptions::default(), Some(0))?;
+/// ```
+pub fn print_scoreboard(out: &mut dyn Write, date: NaiveDate, games: Option<&[&Result<Game, ParseError>]>,
+                        options: &DisplayOptions, selected: Option<usize>) -> io::Result<()> {
+    // clear terminal and set program to write in top left of terminal
+    clear_terminal(out)?;
+    print_date(out, date)?;
+    match games {
+        Some([]) => writeln!(out, "No games")?,
+        Some(games) => {
+            print_header(out, options)?;
+            print_games(out, games, options, selected)?;
+        }
+        None => writeln!(out, "Loading...")?,
+    }
+    return Ok(());
+}
+
+/// Lists the games in the sort order of the options
+///
+/// # Examples
+///
+/// ```
+/// let games = provider.fetch_scoreboard(date).await?;
+/// print_games(&mut stdout(), &arrange_games(&games, &options), &options, None)?;
+/// ```
+pub fn arrange_games<'a>(games: &'a [Result<Game, ParseError>], options: &DisplayOptions)
+    -> Vec<&'a Result<Game, ParseError>> {
+    let mut games: Vec<&Result<Game, ParseError>> = games.iter().collect();
+    // the sort is stable, games that compare equal stay in the order of the page
+    games.sort_by_key(|game| options.sort.key(game, options.is_favorite(game)));
+    return games;
+}
+
+/// Prints every game, or an error row in place of each game that could not be read. The game at
+/// index `selected`, if any, is marked with a cursor in the gutter left of the rows, and the games
+/// of favorite teams with a star ("*").
+pub fn print_games(out: &mut dyn Write, games: &[&Result<Game, ParseError>], options: &DisplayOptions,
+                   selected: Option<usize>) -> io::Result<()> {
+    for (index, game) in games.iter().enumerate() {
+        let cursor = if selected == Some(index) { ">" } else { " " };
+        // plain ASCII like the rest of the row, for plain mode and older terminals
+        let star = if options.is_favorite(game) { "*" } else { " " };
+        write!(out, "{}{}", cursor.bold(), star.yellow().bold())?;
+        // print current game info to terminal
+        match game {
+            Ok(game) => game.display(out, options)?,
+            // keep the rest of the scoreboard even if one game can't be read
+            Err(err) => print_parse_error(out, err, options)?,
+        }
+    }
+    return Ok(());
+}
+
+/// Prints a header for the program in the following format (leaving room for the cursor gutter),
+/// the team columns as wide as the names in the style of the options:
+///         Away             Home          Score       Status
+///         ----             ----          -----       ------
+///
+pub fn print_header(out: &mut dyn Write, options: &DisplayOptions) -> io::Result<()> {
+    let width = options.name_style.width();
+    writeln!(
+        out,
+        "  {:^width$} {:^width$}{:^13}        {:^9}",
+        "Away", "Home", "Score", "Status"
+    )?;
+    writeln!(
+        out,
+        "  {:^width$} {:^width$}{:^13}        {:^9}",
+        "----", "----", "-----", "------"
+    )?;
+    return Ok(());
+}
+
+/// Clears the terminal and prints the detail page of a single game
+///
+/// # Examples
+///
+/// ```
+/// if let Some(Ok(game)) = games.get(selected) {
+///     print_game_detail(&mut stdout(), date, game)?;
+/// }
+/// ```
+pub fn print_game_detail(out: &mut dyn Write, date: NaiveDate, game: &Game) -> io::Result<()> {
+    clear_terminal(out)?;
+    print_date(out, date)?;
+    writeln!(out)?;
+    game.display_detail(out)?;
+    writeln!(out, "{}", "Esc: back to the scoreboard".dimmed())?;
+    return Ok(());
+}
+
+/// Clears the terminal and prints every action of the live scoreboard with the keys bound to it
+///
+/// # Examples
+///
+/// ```
+/// print_help(&mut stdout(), &KeyBindings::default().help())?;
+/// ```
+pub fn print_help(out: &mut dyn Write, help: &[(Action, Vec<String>)]) -> io::Result<()> {
+    clear_terminal(out)?;
+    writeln!(out, "{}\n", "Keys".bold().underline())?;
+    for (action, keys) in help {
+        let keys = if keys.is_empty() { String::from("(none)") } else { keys.join(", ") };
+        writeln!(out, "    {:<16}{}", keys, action.description())?;
+    }
+    writeln!(out, "\n{}", "Press any key to close this help".dimmed())?;
+    return Ok(());
+}
+
+/// Prints the games of several days, each day under its own date header, followed by a summary of
+/// the finished games. Unlike `print_scoreboard` this doesn't clear the terminal, the output is
+/// meant to be scrolled through.
+///
+/// # Examples
+///
+/// ```
+/// let week = fetch_scoreboards(provider, dates).await?;
+/// print_date_range(&mut stdout(), &week, &DisplayOptions::default())?;
+/// ```
+pub fn print_date_range(out: &mut dyn Write, days: &[(NaiveDate, Vec<Result<Game, ParseError>>)],
+                        options: &DisplayOptions) -> io::Result<()> {
+    for (date, games) in days {
+        print_day(out, *date, games, options)?;
+    }
+    return print_range_summary(out, days);
+}
+
+/// Prints the games of a day under its date header, in the sort order of the options. Unlike
+/// `print_scoreboard` this doesn't clear the terminal or move the cursor, so it can be written to a
+/// file or a pipe.
+///
+/// # Examples
+///
+/// ```
+/// let games = provider.fetch_scoreboard(date).await?;
+/// print_day(&mut stdout(), date, &games, &DisplayOptions::default())?;
+/// ```
+pub fn print_day(out: &mut dyn Write, date: NaiveDate, games: &[Result<Game, ParseError>],
+                 options: &DisplayOptions) -> io::Result<()> {
+    print_date(out, date)?;
+    if games.is_empty() {
+        return writeln!(out, "No games\n");
+    }
+    print_header(out, options)?;
+    print_games(out, &arrange_games(games, options), options, None)?;
+    return writeln!(out);
+}
+
+/// Prints the day a scoreboard is for, e.g. "Saturday, October 10 2026"
+fn print_date(out: &mut dyn Write, date: NaiveDate) -> io::Result<()> {
+    writeln!(out, "{}", date.format("%A, %B %-d %Y").to_string().bold().underline())
+}
+
+/// Prints the summary footer of a date range:
+///
+///     Games played: 42   Home wins: 24 (57%)   Away wins: 18 (43%)   Avg total points: 228.4
+fn print_range_summary(out: &mut dyn Write, days: &[(NaiveDate, Vec<Result<Game, ParseError>>)]) -> io::Result<()> {
+    let finished: Vec<&Game> = days
+        .iter()
+        .flat_map(|(_, games)| games.iter().flatten())
+        .filter(|game| game.status.is_final())
+        .collect();
+    let played = finished.len();
+    let home_wins = finished
+        .iter()
+        .filter(|game| game.home_team.score > game.away_team.score)
+        .count();
+    let away_wins = played - home_wins;
+    let total_points: u32 = finished
+        .iter()
+        .map(|game| game.home_team.score + game.away_team.score)
+        .sum();
+
+    // avoid dividing by zero when nothing has been played yet
+    let percent = |wins: usize| if played == 0 { 0.0 } else { 100.0 * wins as f64 / played as f64 };
+    let average_points = if played == 0 { 0.0 } else { total_points as f64 / played as f64 };
+    writeln!(
+        out,
+        "Games played: {}   Home wins: {} ({:.0}%)   Away wins: {} ({:.0}%)   Avg total points: {:.1}",
+        played,
+        home_wins,
+        percent(home_wins),
+        away_wins,
+        percent(away_wins),
+        average_points
+    )
+}
+
+/// Prints the row of a game that could not be parsed, in place of the game itself.
+///
+/// # Examples
+///
+/// ```
+/// match form_game(game_block, date) {
+///     Ok(game) => game.display(&mut stdout(), &options)?,
+///     Err(err) => print_parse_error(&mut stdout(), &err, &options)?,
+/// }
+/// ```
+pub fn print_parse_error(out: &mut dyn Write, err: &ParseError, options: &DisplayOptions) -> io::Result<()> {
+    // spans both team columns
+    let width = 2 * options.name_style.width() + 1;
+    writeln!(out, "{:^width$}{}", "unreadable game".red(), err.to_string().red())
 }
 
 /// Clears the terminal and repositions any output to be written at the top left of the terminal.
@@ -28,19 +294,17 @@ pub fn print_header() {
 ///
 /// Basic usage:
 /// ```
-/// clear_terminal();
+/// clear_terminal(&mut stdout())?;
 /// ```
-pub fn clear_terminal() {
-    write!(stdout(),
-           "{}{}{}",
+pub fn clear_terminal(out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "{}{}{}",
            termion::clear::All,
            termion::cursor::Goto(1, 1),
            termion::cursor::Hide)
-           .unwrap();
 }
 
 /// Clears the terminal, shows the cursor at the top left and flushed stdout. Used when we are
-/// ending the program
+/// ending the program. Does nothing if stdout is not a terminal.
 ///
 /// # Examples
 ///
@@ -49,6 +313,10 @@ pub fn clear_terminal() {
 /// ```
 pub fn cleanup_terminal() {
     let mut stdout = stdout();
+    // nothing was drawn on a terminal, don't leave escape codes in a file or pipe
+    if !termion::is_tty(&stdout) {
+        return;
+    }
     write!(stdout,
            "{}{}{}",
            termion::clear::All,
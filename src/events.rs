@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::game::Game;
+use crate::game_status::{period_name, GameStatus};
+use crate::html_parser::ParseError;
+
+/// Largest score difference of a clutch game
+const CLUTCH_MARGIN: u32 = 5;
+/// Seconds left in the 4th quarter (or an overtime) from which a close game is clutch
+const CLUTCH_SECONDS: u32 = 5 * 60;
+
+/// Something that happened in a game between two refreshes of the scoreboard
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// The game tipped off
+    Started,
+    /// The given quarter or overtime was played out, the game goes on
+    PeriodEnded(u32),
+    /// The team that was behind is now ahead, maybe after a tie
+    LeadChange,
+    /// Within 5 points in the last 5 minutes of the 4th quarter or of an overtime
+    Clutch,
+    /// The game is over
+    Final,
+}
+
+impl EventKind {
+    /// Name of the event in snake case, as in the hooks of the config file (`on_lead_change`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Started => "start",
+            EventKind::PeriodEnded(_) => "period_end",
+            EventKind::LeadChange => "lead_change",
+            EventKind::Clutch => "clutch",
+            EventKind::Final => "final",
+        }
+    }
+}
+
+/// An event of a game, with the game as it stands after the event
+pub struct GameEvent<'a> {
+    pub kind: EventKind,
+    pub game: &'a Game,
+}
+
+impl GameEvent<'_> {
+    /// The event in a few words, with the score: "Lead change: Boston 67 - 69 LA Lakers"
+    pub fn message(&self) -> String {
+        let game = self.game;
+        let score = format!(
+            "{} {} - {} {}",
+            game.away_team.name, game.away_team.score, game.home_team.score, game.home_team.name
+        );
+        match self.kind {
+            EventKind::Started => format!("Tip-off: {} @ {}", game.away_team.name, game.home_team.name),
+            EventKind::PeriodEnded(2) => format!("Halftime: {}", score),
+            EventKind::PeriodEnded(period) => format!("End of {}: {}", period_name(period), score),
+            EventKind::LeadChange => format!("Lead change: {}", score),
+            EventKind::Clutch => format!("Clutch time: {}, {}", score, game.status),
+            EventKind::Final => format!("{}: {}", game.status, score),
+        }
+    }
+}
+
+/// The team ahead in each game when it was last not tied, by away and home team name: `Greater`
+/// for the away team, `Less` for the home team
+pub type Leaders = HashMap<(String, String), Ordering>;
+
+/// Compares two scoreboards of the same day and lists what happened in between, game by game in
+/// the order of `current`. A game is followed by the teams playing it; games only on one of the
+/// scoreboards, or that could not be read on either, have no events.
+///
+/// # Arguments
+///
+/// * `previous` - The scoreboard as it was last fetched
+/// * `current` - The scoreboard just fetched
+/// * `leaders` - The team last ahead in each game, kept from one call to the next so a lead that
+///   changes through a tie spanning refreshes is still a lead change. Start it empty for each day.
+///
+/// # Examples
+///
+/// ```
+/// let mut leaders = Leaders::new();
+/// let games = provider.fetch_scoreboard(date).await?;
+/// for event in diff_games(&previous, &games, &mut leaders) {
+///     println!("{}", event.message());
+/// }
+/// ```
+pub fn diff_games<'a>(previous: &[Result<Game, ParseError>], current: &'a [Result<Game, ParseError>],
+                      leaders: &mut Leaders) -> Vec<GameEvent<'a>> {
+    let mut events = Vec::new();
+    for game in current.iter().flatten() {
+        let matchup = (game.away_team.name.clone(), game.home_team.name.clone());
+        let before = previous.iter().flatten().find(|before| {
+            before.away_team.name == game.away_team.name && before.home_team.name == game.home_team.name
+        });
+        // a game tied on the last scoreboard may have been led by either team before the tie
+        let last_lead = match before.map(lead) {
+            Some(Ordering::Equal) | None => leaders.get(&matchup).copied().unwrap_or(Ordering::Equal),
+            Some(lead) => lead,
+        };
+        if let Some(before) = before {
+            events.extend(game_events(before, game, last_lead).into_iter().map(|kind| GameEvent { kind, game }));
+        }
+        let lead = if lead(game).is_ne() { lead(game) } else { last_lead };
+        if lead.is_ne() {
+            leaders.insert(matchup, lead);
+        }
+    }
+    return events;
+}
+
+/// Which team is ahead in a game: `Greater` for the away team, `Less` for the home team
+fn lead(game: &Game) -> Ordering {
+    return game.away_team.score.cmp(&game.home_team.score);
+}
+
+/// What happened in a game between two of its states, `last_lead` being the team last ahead
+/// before the second one
+fn game_events(before: &Game, after: &Game, last_lead: Ordering) -> Vec<EventKind> {
+    let mut events = Vec::new();
+    if !before.status.has_started() && after.status.has_started() {
+        events.push(EventKind::Started);
+    }
+    // a break can be missed between two refreshes, the period moving on tells it ended too
+    let period_ended = match (&before.status, &after.status) {
+        (from, GameStatus::Halftime) if from != &after.status => Some(2),
+        (from, GameStatus::EndOfPeriod { period }) if from != &after.status => Some(*period),
+        (GameStatus::InProgress { period: from, .. }, GameStatus::InProgress { period: to, .. }) if to > from => {
+            Some(to - 1)
+        }
+        _ => None,
+    };
+    if let Some(period) = period_ended {
+        events.push(EventKind::PeriodEnded(period));
+    }
+    // a tie doesn't change the lead: the team now ahead must have trailed when a team last led
+    if before.status.has_started() && lead(after).is_ne() && last_lead.reverse() == lead(after) {
+        events.push(EventKind::LeadChange);
+    }
+    if is_clutch(after) && !is_clutch(before) {
+        events.push(EventKind::Clutch);
+    }
+    if !before.status.is_final() && after.status.is_final() {
+        events.push(EventKind::Final);
+    }
+    return events;
+}
+
+/// True for a game within 5 points in the last 5 minutes of the 4th quarter or of an overtime
+fn is_clutch(game: &Game) -> bool {
+    let GameStatus::InProgress { period, .. } = game.status else { return false };
+    let close = game.away_team.score.abs_diff(game.home_team.score) <= CLUTCH_MARGIN;
+    return period >= 4 && close && game.status.seconds_left().is_some_and(|seconds| seconds <= CLUTCH_SECONDS);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{create_nonstarted_game, GameInfo};
+
+    /// Boston @ LA Lakers, with the given status text and scores
+    fn game(status: &str, away_score: u32, home_score: u32) -> Result<Game, ParseError> {
+        let status = GameStatus::parse(status).unwrap();
+        let mut game = create_nonstarted_game("LA Lakers", "Boston", status, GameInfo::default());
+        game.away_team.score = away_score;
+        game.home_team.score = home_score;
+        return Ok(game);
+    }
+
+    fn scheduled() -> Result<Game, ParseError> {
+        let status = GameStatus::Scheduled { start: String::from("7:30 PM ET") };
+        return Ok(create_nonstarted_game("LA Lakers", "Boston", status, GameInfo::default()));
+    }
+
+    /// The events between each scoreboard and the next, one list per refresh
+    fn replay(scoreboards: &[Result<Game, ParseError>]) -> Vec<Vec<EventKind>> {
+        let mut leaders = Leaders::new();
+        return scoreboards
+            .windows(2)
+            .map(|pair| diff_games(&pair[..1], &pair[1..], &mut leaders).iter().map(|event| event.kind).collect())
+            .collect();
+    }
+
+    #[test]
+    fn sees_tip_offs_periods_and_finals() {
+        let events = replay(&[scheduled(), game("1st 11:40", 2, 0), game("End 1st", 30, 24),
+                              game("Halftime", 55, 50), game("4:10 3rd", 80, 71), game("2:00 4th", 104, 93),
+                              game("Final", 110, 101)]);
+        assert_eq!(events, vec![
+            vec![EventKind::Started],
+            vec![EventKind::PeriodEnded(1)],
+            vec![EventKind::PeriodEnded(2)],
+            vec![],
+            // the end of the 3rd quarter fell between two refreshes
+            vec![EventKind::PeriodEnded(3)],
+            vec![EventKind::Final],
+        ]);
+    }
+
+    #[test]
+    fn sees_one_tip_off_through_a_delay() {
+        let mut delayed = game("1st 8:00", 12, 10);
+        if let Ok(game) = &mut delayed {
+            game.status = GameStatus::Delayed { started: true };
+        }
+        let events = replay(&[scheduled(), game("1st 9:00", 10, 10), delayed, game("1st 7:40", 12, 12)]);
+        assert_eq!(events, vec![vec![EventKind::Started], vec![], vec![]]);
+    }
+
+    #[test]
+    fn sees_lead_changes() {
+        let events = replay(&[game("2nd 5:00", 40, 38), game("2nd 4:30", 40, 42), game("2nd 4:00", 44, 42)]);
+        assert_eq!(events, vec![vec![EventKind::LeadChange], vec![EventKind::LeadChange]]);
+    }
+
+    #[test]
+    fn sees_lead_changes_through_a_tie() {
+        let events = replay(&[game("2nd 5:00", 40, 38), game("2nd 4:30", 40, 40), game("2nd 4:00", 40, 43)]);
+        assert_eq!(events, vec![vec![], vec![EventKind::LeadChange]]);
+    }
+
+    #[test]
+    fn ignores_ties_won_back() {
+        let events = replay(&[game("2nd 5:00", 40, 38), game("2nd 4:30", 40, 40), game("2nd 4:00", 43, 40)]);
+        assert_eq!(events, vec![vec![], vec![]]);
+    }
+
+    #[test]
+    fn sees_clutch_time() {
+        let events = replay(&[game("4th 5:30", 98, 95), game("4th 4:59", 98, 95), game("4th 2:00", 100, 95)]);
+        assert_eq!(events, vec![vec![EventKind::Clutch], vec![]]);
+        // out of it when the margin grows, back in when it shrinks
+        let events = replay(&[game("4th 3:00", 98, 90), game("4th 1:00", 98, 94)]);
+        assert_eq!(events, vec![vec![EventKind::Clutch]]);
+    }
+
+    #[test]
+    fn sees_clutch_time_in_overtime() {
+        let events = replay(&[game("4th 0:02", 100, 98), game("End 4th", 100, 100), game("OT 4:12", 102, 104)]);
+        assert_eq!(events, vec![vec![EventKind::PeriodEnded(4)], vec![EventKind::LeadChange, EventKind::Clutch]]);
+        // an overtime lasts 5 minutes, a close one is clutch from its tip-off
+        let events = replay(&[game("End 4th", 100, 100), game("OT 5:00", 100, 100), game("OT 4:12", 102, 100)]);
+        assert_eq!(events, vec![vec![EventKind::Clutch], vec![]]);
+    }
+
+    #[test]
+    fn knows_clutch_games() {
+        assert!(is_clutch(&game("4th 5:00", 100, 95).unwrap()));
+        assert!(is_clutch(&game("45.2 4th", 100, 95).unwrap()));
+        assert!(is_clutch(&game("2OT 0:31", 120, 121).unwrap()));
+        assert!(!is_clutch(&game("4th 5:01", 100, 95).unwrap()));
+        assert!(!is_clutch(&game("4th 1:00", 100, 94).unwrap()));
+        assert!(!is_clutch(&game("3rd 1:00", 70, 70).unwrap()));
+        assert!(!is_clutch(&game("End 4th", 100, 100).unwrap()));
+        // no clock, no way to tell
+        assert!(!is_clutch(&game("4th", 100, 100).unwrap()));
+    }
+
+    #[test]
+    fn follows_games_by_their_teams() {
+        let mut leaders = Leaders::new();
+        let other = Ok(create_nonstarted_game("Chicago", "Miami", GameStatus::Final, GameInfo::default()));
+        let current = [game("Final", 100, 90)];
+        assert!(diff_games(&[other], &current, &mut leaders).is_empty());
+        let current = [Err(ParseError::MissingTeamNames)];
+        assert!(diff_games(&[game("4th 0:10", 100, 90)], &current, &mut leaders).is_empty());
+    }
+}
@@ -0,0 +1,89 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use select::document::Document;
+
+use crate::game::Game;
+use crate::html_parser::{parse_scoreboard, ParseError};
+use crate::provider::{ProviderError, ScoreProvider};
+
+/// Replays scoreboard pages saved to disk instead of fetching them from a website. The requested
+/// date is ignored -- whatever was saved is what gets shown.
+///
+/// When given several files, every fetch returns the next file (in file name order), which
+/// simulates a live night when the files are timestamped snapshots. Once the last file is reached
+/// it keeps being returned.
+pub struct FixtureProvider {
+    files: Vec<PathBuf>,
+    next: AtomicUsize,
+}
+
+impl FixtureProvider {
+    /// Creates a provider that always returns the scoreboard saved in `path`
+    pub fn from_file(path: &Path) -> Result<FixtureProvider, ProviderError> {
+        // fail before anything is shown, and name the file
+        let metadata = std::fs::metadata(path).map_err(|err| with_path(path, err))?;
+        if !metadata.is_file() {
+            return Err(ProviderError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a file", path.display()),
+            )));
+        }
+        return Ok(FixtureProvider {
+            files: vec![path.to_path_buf()],
+            next: AtomicUsize::new(0),
+        });
+    }
+
+    /// Creates a provider that replays every .html file found in `dir`, one per fetch
+    pub fn from_dir(dir: &Path) -> Result<FixtureProvider, ProviderError> {
+        let mut files: Vec<PathBuf> = Vec::new();
+        let entries = std::fs::read_dir(dir).map_err(|err| with_path(dir, err))?;
+        for entry in entries {
+            let path = entry?.path();
+            let is_html = path
+                .extension()
+                .is_some_and(|ext| ext == "html" || ext == "htm");
+            if path.is_file() && is_html {
+                files.push(path);
+            }
+        }
+        if files.is_empty() {
+            return Err(ProviderError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no .html fixtures found in {}", dir.display()),
+            )));
+        }
+        // recorded snapshots start with their fetch timestamp, so name order is replay order
+        files.sort();
+
+        return Ok(FixtureProvider {
+            files,
+            next: AtomicUsize::new(0),
+        });
+    }
+
+    /// Returns the file to replay for this fetch and moves on to the next one
+    fn next_file(&self) -> &Path {
+        let index = self.next.fetch_add(1, Ordering::Relaxed);
+        return &self.files[index.min(self.files.len() - 1)];
+    }
+}
+
+#[async_trait]
+impl ScoreProvider for FixtureProvider {
+    async fn fetch_scoreboard(&self, date: NaiveDate) -> Result<Vec<Result<Game, ParseError>>, ProviderError> {
+        let path = self.next_file();
+        let html = tokio::fs::read_to_string(path).await.map_err(|err| with_path(path, err))?;
+        let document = Document::from(&*html);
+        return Ok(parse_scoreboard(&document, date));
+    }
+}
+
+/// Adds the path to an io error, the bare error doesn't say which file or directory it was about
+fn with_path(path: &Path, err: io::Error) -> io::Error {
+    return io::Error::new(err.kind(), format!("{}: {}", path.display(), err));
+}
@@ -1,70 +1,213 @@
-use select::predicate::{Class, Name, Predicate};
+use std::io::{self, Write};
 
 use crate::team::Team;
-use colored::Colorize;
-use crate::timezones::TimeZone;
-use crate::constants::{TEAM_COLORS, MY_TIMEZONE};
+use crate::teams::{NameStyle, TeamInfo};
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::Serialize;
+use crate::colors::{paint_badge, paint_bg};
+use crate::game_status::GameStatus;
+use crate::display::DisplayOptions;
 
+#[derive(Serialize)]
 pub struct Game {
-    pub has_started: bool,
+    pub status: GameStatus,
     pub away_team: Team,
     pub home_team: Team,
-    pub game_time: String,
+    #[serde(flatten)]
+    pub info: GameInfo,
+}
+
+/// Details about a game that are not always on the scoreboard page
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct GameInfo {
+    // start time in the time zone the program is set to
+    pub start_time: Option<String>,
+    // when the game tips off, if the page gives a time
+    #[serde(skip)]
+    pub start: Option<DateTime<Tz>>,
+    // arena the game is played in
+    pub venue: Option<String>,
+    // TV network(s) showing the game
+    pub broadcast: Option<String>,
 }
 
 impl Game {
-    pub fn display(&self) {
-        println!(
-            "{}",
-            format!(
-                "{:^16}@{:^16}{:^5} - {:^5}\t{:^9}",
-                &self.away_team.name[..].on_truecolor(self.away_team.color_value.0, self.away_team.color_value.1, self.away_team.color_value.2),
-                &self.home_team.name[..].on_truecolor(self.home_team.color_value.0, self.home_team.color_value.1, self.home_team.color_value.2),
-                self.away_team.score,
-                self.home_team.score,
-                self.game_time
-            )
-        );
+    /// Whether one of the given teams plays in the game. Teams the registry doesn't know never
+    /// match.
+    pub fn involves(&self, teams: &[&'static TeamInfo]) -> bool {
+        return [&self.away_team, &self.home_team]
+            .iter()
+            .filter_map(|team| team.info)
+            .any(|info| teams.iter().any(|team| team.abbreviation == info.abbreviation));
+    }
+
+    /// Writes the row of the game on the scoreboard, followed by its line score and leaders when
+    /// the options ask for them
+    pub fn display(&self, out: &mut dyn Write, options: &DisplayOptions) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}@{}{:^5} - {:^5}        {:^9}",
+            name_badge(&self.away_team, options.name_style),
+            name_badge(&self.home_team, options.name_style),
+            self.away_team.score,
+            self.home_team.score,
+            self.status.to_string()
+        )?;
+        if options.linescore && self.status.has_started() {
+            self.display_linescore(out, options.name_style)?;
+        }
         // if game has started, then print the stat leaders
+        if options.leaders && self.status.has_started() {
+            self.display_leaders(out, options.name_style)?;
+        }
+        return Ok(());
     }
-}
 
-pub fn create_nonstarted_game(home_team_name: &str, away_team_name: &str, game_block: select::node::Node) -> Game {
-    //let away_team = Team::default(away_team_name);
-    // TODO: need to put team color in here
-    let home_team = Team {
-        name: String::from(home_team_name),
-        color_value: TEAM_COLORS[&home_team_name[..]],
-        ..Team::default()
-    };
-    let away_team = Team {
-        name: String::from(away_team_name),
-        color_value: TEAM_COLORS[&away_team_name[..]],
-        ..Team::default()
-    };
-    // find game start time based on Timezone
-    let time_zones: Vec<String> = game_block
-        .find(Class("shsTimezone"))
-        .map(|tag| tag.text())
-        .collect::<Vec<String>>();
+    /// Prints the points, rebounds and assists leaders of both teams side by side, each on its
+    /// team's color like the team names (team colors as text would be unreadable on some
+    /// backgrounds). The home team's leaders start under the Home column of the scoreboard in the
+    /// given name style, or further right when an away leader doesn't fit before it.
+    ///
+    ///     PTS  Jayson Tatum 22   LeBron James 19
+    ///     REB  Al Horford 8      Anthony Davis 11
+    ///     AST  Jrue Holiday 6    LeBron James 7
+    fn display_leaders(&self, out: &mut dyn Write, style: NameStyle) -> io::Result<()> {
+        // "    PTS " is written before the away leader
+        const PREFIX_WIDTH: usize = 8;
+        let leader = |name: &str, value: u32| match name {
+            "" => String::new(),
+            name => format!(" {} {} ", name, value),
+        };
+        let rows: Vec<(&str, String, String)> = self
+            .away_team
+            .leaders()
+            .iter()
+            .zip(self.home_team.leaders().iter())
+            // right after tip-off the page may not list any leader yet
+            .filter(|((_, away_name, _), (_, home_name, _))| !away_name.is_empty() || !home_name.is_empty())
+            .map(|((category, away_name, away_value), (_, home_name, home_value))| {
+                (*category, leader(away_name, *away_value), leader(home_name, *home_value))
+            })
+            .collect();
 
-    // TODO/refactor: FUNCTIONALIZE
-    //game_time = get_game_start_time(game_block);
-    let game_time = match MY_TIMEZONE {
-        TimeZone::Pacific => String::from(time_zones.get(0).expect("Could not read time zone")),
-        TimeZone::Mountain => {
-            String::from(time_zones.get(1).expect("Could not read time zone"))
+        // the Home column starts past the gutter, the away names and the "@" (see `print_header`)
+        let home_column = 2 + style.width() + 1;
+        let widest_away = rows.iter().map(|(_, away, _)| away.chars().count() + 1).max().unwrap_or(0);
+        let away_width = (home_column - PREFIX_WIDTH).max(widest_away);
+        for (category, away, home) in rows {
+            // pad outside of the badge, the badge is only as wide as its text
+            let padding = " ".repeat(away_width - away.chars().count());
+            writeln!(
+                out,
+                "    {} {}{}{}",
+                category,
+                paint_badge(&away, self.away_team.color_value),
+                padding,
+                paint_badge(&home, self.home_team.color_value)
+            )?;
         }
-        TimeZone::Central => String::from(time_zones.get(2).expect("Could not read time zone")),
-        TimeZone::Eastern => String::from(time_zones.get(3).expect("Could not read time zone")),
-    };
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    /// Prints everything known about the game on its own page: the teams, status, start time,
+    /// venue and broadcast (when the page offers them), line score and leaders of both teams.
+    pub fn display_detail(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{} @ {}\n",
+            two_tone_badge(&self.away_team),
+            two_tone_badge(&self.home_team)
+        )?;
+        for (side, team) in [("Away", &self.away_team), ("Home", &self.home_team)] {
+            if let Some(info) = team.info {
+                writeln!(out, "    {:<10}{} division ({})", side, info.division, info.conference())?;
+            }
+        }
+        writeln!(out, "    {:<10}{}", "Status", self.status)?;
+        if let Some(start_time) = &self.info.start_time {
+            writeln!(out, "    {:<10}{}", "Start", start_time)?;
+        }
+        if let Some(venue) = &self.info.venue {
+            writeln!(out, "    {:<10}{}", "Venue", venue)?;
+        }
+        if let Some(broadcast) = &self.info.broadcast {
+            writeln!(out, "    {:<10}{}", "TV", broadcast)?;
+        }
+        writeln!(out)?;
 
+        if self.status.has_started() {
+            self.display_linescore(out, NameStyle::Short)?;
+            self.display_leaders(out, NameStyle::Short)?;
+        }
+        return Ok(());
+    }
+
+    /// Prints the box score line score of the game: the points of both teams in every period,
+    /// including overtimes, and the final total.
+    ///
+    ///     Team              1    2    3    4   OT     T
+    ///     Boston           30   25   12   22   11   100
+    ///     LA Lakers        20   28   15   29    9   101
+    fn display_linescore(&self, out: &mut dyn Write, name_style: NameStyle) -> io::Result<()> {
+        // always show the 4 quarters, even before they are played
+        let n_periods = self
+            .away_team
+            .period_scores
+            .len()
+            .max(self.home_team.period_scores.len())
+            .max(4);
+
+        let header: String = (0..n_periods)
+            .map(|period| format!("{:>5}", period_label(period)))
+            .collect();
+        let width = name_style.width().max(14);
+        writeln!(out, "    {:<width$}{}{:>6}", "Team", header, "T")?;
+        for team in [&self.away_team, &self.home_team] {
+            let scores: String = (0..n_periods)
+                .map(|period| match team.period_scores.get(period) {
+                    Some(score) => format!("{:>5}", score),
+                    None => format!("{:>5}", ""),
+                })
+                .collect();
+            writeln!(out, "    {:<width$}{}{:>6}", team.display_name(name_style), scores, team.score)?;
+        }
+        writeln!(out)?;
+        return Ok(());
+    }
+}
+
+/// The name of a team centered on its color, as wide as the name column of the scoreboard
+fn name_badge(team: &Team, style: NameStyle) -> String {
+    let name = format!("{:^width$}", team.display_name(style), width = style.width());
+    return paint_badge(&name, team.color_value);
+}
+
+/// The full name of a team on its colors: the team color with readable text, edged with the team's
+/// second color
+fn two_tone_badge(team: &Team) -> String {
+    let edge = paint_bg(" ", team.secondary_color_value);
+    let name = format!(" {} ", team.display_name(NameStyle::Full));
+    return format!("{}{}{}", edge, paint_badge(&name, team.color_value), edge);
+}
+
+/// Returns the name of a period from its index: 1 through 4 for the quarters, then OT, 2OT, ...
+pub fn period_label(period: usize) -> String {
+    match period {
+        0..=3 => (period + 1).to_string(),
+        4 => String::from("OT"),
+        _ => format!("{}OT", period - 3),
+    }
+}
+
+/// Creates a game that has no scores or leaders (yet) for the two teams
+pub fn create_nonstarted_game(home_team_name: &str, away_team_name: &str, status: GameStatus, info: GameInfo) -> Game {
     let game = Game {
-        has_started: false,
-        away_team,
-        home_team,
-        game_time,
+        status,
+        away_team: Team::new(String::from(away_team_name)),
+        home_team: Team::new(String::from(home_team_name)),
+        info,
     };
     return game;
 }
-
@@ -0,0 +1,301 @@
+use std::fmt;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::html_parser::ParseError;
+
+/// Where a game stands. Periods are counted from 1: the 4 quarters, then 5 for the first
+/// overtime, 6 for the second, ...
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameStatus {
+    /// Not started yet, tipping off at `start` (as shown by the site)
+    Scheduled { start: String },
+    /// Being played, `clock` is the time left in the period (may be empty between possessions)
+    InProgress { period: u32, clock: String },
+    Halftime,
+    /// The break after `period` (other than halftime)
+    EndOfPeriod { period: u32 },
+    /// Finished in regulation
+    Final,
+    /// Finished after the given number of overtimes
+    FinalOT(u32),
+    Postponed,
+    Cancelled,
+    /// Held up, before tip-off or while being played (`started`, the game has scores)
+    Delayed { started: bool },
+}
+
+impl GameStatus {
+    /// Parses the status text of a game that is not simply scheduled, e.g. "Final", "Final/2OT",
+    /// "3rd Qtr 5:32", "Halftime", "End 1st", "Postponed".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let status = GameStatus::parse("5:32 3rd")?;
+    /// assert_eq!(status, GameStatus::InProgress { period: 3, clock: String::from("5:32") });
+    /// ```
+    pub fn parse(text: &str) -> Result<GameStatus, ParseError> {
+        let lowered = text.trim().to_lowercase();
+        // "Final/OT", "Final - 2OT", "End of 1st" ... -> separate words
+        let words: Vec<&str> = lowered
+            .split(|c: char| c.is_whitespace() || c == '/' || c == '-' || c == ',')
+            .filter(|word| !word.is_empty())
+            .collect();
+        let unknown = || ParseError::UnknownStatus(String::from(text.trim()));
+
+        let first = *words.first().ok_or_else(unknown)?;
+        if first.starts_with("postpone") || first == "ppd" {
+            return Ok(GameStatus::Postponed);
+        }
+        if first.starts_with("cancel") {
+            return Ok(GameStatus::Cancelled);
+        }
+        if first.starts_with("delay") {
+            // the status text alone can't tell whether play had started, see `has_scores`
+            return Ok(GameStatus::Delayed { started: false });
+        }
+        if first == "final" || first == "f" {
+            return match words.iter().find_map(|word| parse_period(word)) {
+                Some(period) if period > 4 => Ok(GameStatus::FinalOT(period - 4)),
+                _ => Ok(GameStatus::Final),
+            };
+        }
+        if first.starts_with("half") {
+            return Ok(GameStatus::Halftime);
+        }
+        let period = words.iter().find_map(|word| parse_period(word));
+        if first == "end" {
+            return match period {
+                Some(2) => Ok(GameStatus::Halftime),
+                Some(period) => Ok(GameStatus::EndOfPeriod { period }),
+                None => Err(unknown()),
+            };
+        }
+
+        // anything else has to be a game being played: a period with an optional clock
+        let period = period.ok_or_else(unknown)?;
+        let clock = words
+            .iter()
+            .find(|word| is_clock(word))
+            .map(|word| String::from(*word))
+            .unwrap_or_default();
+        return Ok(GameStatus::InProgress { period, clock });
+    }
+
+    /// True once the ball has been tipped, scores and leaders are only available from then on
+    pub fn has_started(&self) -> bool {
+        !matches!(
+            self,
+            GameStatus::Scheduled { .. } | GameStatus::Postponed | GameStatus::Cancelled
+                | GameStatus::Delayed { started: false }
+        )
+    }
+
+    /// True if the game is over (in regulation or overtime)
+    pub fn is_final(&self) -> bool {
+        matches!(self, GameStatus::Final | GameStatus::FinalOT(_))
+    }
+
+    /// True while the game is being played, or held up (delayed) while it is
+    pub fn is_live(&self) -> bool {
+        matches!(self, GameStatus::Delayed { .. }) || (self.has_started() && !self.is_final())
+    }
+
+    /// Whole seconds left in the period being played, None when the clock isn't running (or isn't
+    /// shown). The last minute is shown with tenths of a second: "5:32" and "45.2" are 332 and 45.
+    pub fn seconds_left(&self) -> Option<u32> {
+        let GameStatus::InProgress { clock, .. } = self else { return None };
+        let whole = clock.split('.').next()?;
+        return match whole.split_once(':') {
+            Some((minutes, seconds)) => Some(minutes.parse::<u32>().ok()? * 60 + seconds.parse::<u32>().ok()?),
+            None => whole.parse::<u32>().ok(),
+        };
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStatus::Scheduled { start } => write!(f, "{}", start),
+            GameStatus::InProgress { period, clock } if clock.is_empty() => {
+                write!(f, "{}", period_name(*period))
+            }
+            GameStatus::InProgress { period, clock } => {
+                write!(f, "{} {}", period_name(*period), clock)
+            }
+            GameStatus::Halftime => write!(f, "Halftime"),
+            GameStatus::EndOfPeriod { period } => write!(f, "End {}", period_name(*period)),
+            GameStatus::Final => write!(f, "Final"),
+            GameStatus::FinalOT(1) => write!(f, "Final/OT"),
+            GameStatus::FinalOT(overtimes) => write!(f, "Final/{}OT", overtimes),
+            GameStatus::Postponed => write!(f, "Postponed"),
+            GameStatus::Cancelled => write!(f, "Cancelled"),
+            GameStatus::Delayed { .. } => write!(f, "Delayed"),
+        }
+    }
+}
+
+impl Serialize for GameStatus {
+    /// Serializes the state of the game in snake case with whatever goes with it, followed by the
+    /// status as displayed:
+    ///
+    /// ```json
+    /// {"state": "in_progress", "period": 3, "clock": "5:32", "text": "3rd 5:32"}
+    /// {"state": "final_ot", "overtimes": 2, "text": "Final/2OT"}
+    /// ```
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self {
+            GameStatus::Scheduled { start } => {
+                map.serialize_entry("state", "scheduled")?;
+                map.serialize_entry("start", start)?;
+            }
+            GameStatus::InProgress { period, clock } => {
+                map.serialize_entry("state", "in_progress")?;
+                map.serialize_entry("period", period)?;
+                map.serialize_entry("clock", clock)?;
+            }
+            GameStatus::Halftime => map.serialize_entry("state", "halftime")?,
+            GameStatus::EndOfPeriod { period } => {
+                map.serialize_entry("state", "end_of_period")?;
+                map.serialize_entry("period", period)?;
+            }
+            GameStatus::Final => map.serialize_entry("state", "final")?,
+            GameStatus::FinalOT(overtimes) => {
+                map.serialize_entry("state", "final_ot")?;
+                map.serialize_entry("overtimes", overtimes)?;
+            }
+            GameStatus::Postponed => map.serialize_entry("state", "postponed")?,
+            GameStatus::Cancelled => map.serialize_entry("state", "cancelled")?,
+            GameStatus::Delayed { started } => {
+                map.serialize_entry("state", "delayed")?;
+                map.serialize_entry("started", started)?;
+            }
+        }
+        map.serialize_entry("text", &self.to_string())?;
+        return map.end();
+    }
+}
+
+/// Returns the name of a period as it reads in a status: 1st, 2nd, 3rd, 4th, OT, 2OT, ...
+pub fn period_name(period: u32) -> String {
+    match period {
+        1 => String::from("1st"),
+        2 => String::from("2nd"),
+        3 => String::from("3rd"),
+        4 => String::from("4th"),
+        5 => String::from("OT"),
+        _ => format!("{}OT", period.saturating_sub(4)),
+    }
+}
+
+/// Reads a period out of a single (lowercase) word: "1st" through "4th", "q1" through "q4", "ot",
+/// "2ot", ...
+fn parse_period(word: &str) -> Option<u32> {
+    match word {
+        "1st" | "q1" => Some(1),
+        "2nd" | "q2" => Some(2),
+        "3rd" | "q3" => Some(3),
+        "4th" | "q4" => Some(4),
+        "ot" => Some(5),
+        _ => {
+            // "2ot", "3ot", ...
+            let overtimes = word.strip_suffix("ot")?.parse::<u32>().ok()?;
+            Some(4 + overtimes)
+        }
+    }
+}
+
+/// True if the word looks like the time left in a period, "5:32" or "24.3"
+fn is_clock(word: &str) -> bool {
+    let digits = word.replace([':', '.'], "");
+    word.len() > digits.len()
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn in_progress(period: u32, clock: &str) -> GameStatus {
+        GameStatus::InProgress { period, clock: String::from(clock) }
+    }
+
+    #[test]
+    fn parses_finals() {
+        assert_eq!(GameStatus::parse("Final").unwrap(), GameStatus::Final);
+        assert_eq!(GameStatus::parse("F").unwrap(), GameStatus::Final);
+        assert_eq!(GameStatus::parse("Final/OT").unwrap(), GameStatus::FinalOT(1));
+        assert_eq!(GameStatus::parse("Final/2OT").unwrap(), GameStatus::FinalOT(2));
+        assert_eq!(GameStatus::parse("Final - 3OT").unwrap(), GameStatus::FinalOT(3));
+        // a final after the 4th quarter is a final in regulation
+        assert_eq!(GameStatus::parse("Final/4th").unwrap(), GameStatus::Final);
+    }
+
+    #[test]
+    fn parses_breaks() {
+        assert_eq!(GameStatus::parse("Halftime").unwrap(), GameStatus::Halftime);
+        assert_eq!(GameStatus::parse("End 2nd").unwrap(), GameStatus::Halftime);
+        assert_eq!(GameStatus::parse("End of 1st").unwrap(), GameStatus::EndOfPeriod { period: 1 });
+        assert_eq!(GameStatus::parse("End 4th").unwrap(), GameStatus::EndOfPeriod { period: 4 });
+        assert_eq!(GameStatus::parse("End OT").unwrap(), GameStatus::EndOfPeriod { period: 5 });
+        assert!(GameStatus::parse("End").is_err());
+    }
+
+    #[test]
+    fn parses_games_being_played() {
+        assert_eq!(GameStatus::parse("3rd Qtr 5:32").unwrap(), in_progress(3, "5:32"));
+        assert_eq!(GameStatus::parse("5:32 3rd").unwrap(), in_progress(3, "5:32"));
+        assert_eq!(GameStatus::parse("45.2 4th").unwrap(), in_progress(4, "45.2"));
+        assert_eq!(GameStatus::parse("Q1 12:00").unwrap(), in_progress(1, "12:00"));
+        assert_eq!(GameStatus::parse("2OT 0:31").unwrap(), in_progress(6, "0:31"));
+        // between possessions the clock may be missing
+        assert_eq!(GameStatus::parse("4th").unwrap(), in_progress(4, ""));
+    }
+
+    #[test]
+    fn parses_games_not_played() {
+        assert_eq!(GameStatus::parse("Postponed").unwrap(), GameStatus::Postponed);
+        assert_eq!(GameStatus::parse("PPD").unwrap(), GameStatus::Postponed);
+        assert_eq!(GameStatus::parse("Cancelled").unwrap(), GameStatus::Cancelled);
+        assert_eq!(GameStatus::parse("Delayed").unwrap(), GameStatus::Delayed { started: false });
+    }
+
+    #[test]
+    fn reads_the_game_clock() {
+        assert_eq!(GameStatus::parse("5:32 3rd").unwrap().seconds_left(), Some(332));
+        assert_eq!(GameStatus::parse("45.2 4th").unwrap().seconds_left(), Some(45));
+        assert_eq!(GameStatus::parse("OT 0:00").unwrap().seconds_left(), Some(0));
+        assert_eq!(GameStatus::parse("4th").unwrap().seconds_left(), None);
+        assert_eq!(GameStatus::parse("Halftime").unwrap().seconds_left(), None);
+        assert_eq!(GameStatus::Final.seconds_left(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_statuses() {
+        assert!(matches!(GameStatus::parse(""), Err(ParseError::UnknownStatus(_))));
+        assert!(matches!(GameStatus::parse("7:30 PM ET"), Err(ParseError::UnknownStatus(_))));
+        assert!(matches!(GameStatus::parse("Suspended"), Err(ParseError::UnknownStatus(_))));
+    }
+
+    #[test]
+    fn knows_delayed_games_under_way() {
+        let before_tip_off = GameStatus::Delayed { started: false };
+        let under_way = GameStatus::Delayed { started: true };
+        assert!(!before_tip_off.has_started());
+        assert!(under_way.has_started());
+        assert!(before_tip_off.is_live() && under_way.is_live());
+        assert_eq!(under_way.to_string(), "Delayed");
+    }
+
+    #[test]
+    fn displays_statuses() {
+        assert_eq!(in_progress(3, "5:32").to_string(), "3rd 5:32");
+        assert_eq!(in_progress(5, "").to_string(), "OT");
+        assert_eq!(GameStatus::EndOfPeriod { period: 1 }.to_string(), "End 1st");
+        assert_eq!(GameStatus::FinalOT(1).to_string(), "Final/OT");
+        assert_eq!(GameStatus::FinalOT(2).to_string(), "Final/2OT");
+    }
+}
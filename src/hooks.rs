@@ -0,0 +1,123 @@
+use std::process::Stdio;
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::events::{EventKind, GameEvent};
+
+/// Shell commands run when something happens in a game, from the `[hooks]` of the config file.
+/// Each command gets the game as JSON on its stdin and the event in environment variables:
+///
+/// ```toml
+/// [hooks]
+/// on_final = "notify-send \"$NBA_MESSAGE\""
+/// on_clutch = "curl -s -d @- https://chat.example.com/hook"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    pub on_start: Option<String>,
+    pub on_period_end: Option<String>,
+    pub on_lead_change: Option<String>,
+    pub on_clutch: Option<String>,
+    pub on_final: Option<String>,
+}
+
+impl Hooks {
+    /// The command run for an event, if there is one
+    fn command(&self, kind: EventKind) -> Option<&str> {
+        let command = match kind {
+            EventKind::Started => &self.on_start,
+            EventKind::PeriodEnded(_) => &self.on_period_end,
+            EventKind::LeadChange => &self.on_lead_change,
+            EventKind::Clutch => &self.on_clutch,
+            EventKind::Final => &self.on_final,
+        };
+        return command.as_deref();
+    }
+
+    /// Starts the command of each event in the background, with `sh -c`, and returns the tasks
+    /// running them. Why a command could not be run, or failed, is sent to `failures`; what it
+    /// writes is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The day of the scoreboard the events happened on
+    /// * `events` - What happened since the last refresh
+    /// * `failures` - Where failed commands are reported
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let (failures, mut failed) = mpsc::unbounded_channel();
+    /// config.hooks.run(date, &diff_games(&previous, &games, &mut leaders), &failures);
+    /// ```
+    pub fn run(&self, date: NaiveDate, events: &[GameEvent], failures: &mpsc::UnboundedSender<String>)
+        -> Vec<JoinHandle<()>> {
+        let mut tasks = Vec::new();
+        for event in events {
+            let Some(command) = self.command(event.kind) else { continue };
+            let name = format!("on_{}", event.kind.name());
+            // the game is written out now, the command runs after the scoreboard has moved on
+            let json = serde_json::to_string(event.game).unwrap_or_default();
+            let mut process = Command::new("sh");
+            process
+                .arg("-c")
+                .arg(command)
+                .envs(environment(date, event))
+                .stdin(Stdio::piped())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped());
+            let failures = failures.clone();
+            tasks.push(tokio::spawn(async move {
+                if let Err(failure) = run_command(process, json).await {
+                    // failures are dropped once nobody reports them any more
+                    let _ = failures.send(format!("hook {} failed: {}", name, failure));
+                }
+            }));
+        }
+        return tasks;
+    }
+}
+
+/// Runs a hook command to the end, writing the game to its stdin. A command that exits with an
+/// error is reported with the last line it wrote to stderr.
+async fn run_command(mut process: Command, json: String) -> Result<(), String> {
+    let mut child = process.spawn().map_err(|err| err.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // a command that doesn't read its stdin closes it early, that's not a failure
+        let _ = stdin.write_all(json.as_bytes()).await;
+    }
+    let output = child.wait_with_output().await.map_err(|err| err.to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    return match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => Err(format!("{}: {}", output.status, line.trim())),
+        None => Err(output.status.to_string()),
+    };
+}
+
+/// The environment variables a hook command gets about its event
+fn environment(date: NaiveDate, event: &GameEvent) -> Vec<(&'static str, String)> {
+    let game = event.game;
+    let mut variables = vec![
+        ("NBA_EVENT", event.kind.name().to_string()),
+        ("NBA_MESSAGE", event.message()),
+        ("NBA_DATE", date.to_string()),
+        ("NBA_AWAY", game.away_team.name.clone()),
+        ("NBA_HOME", game.home_team.name.clone()),
+        ("NBA_AWAY_SCORE", game.away_team.score.to_string()),
+        ("NBA_HOME_SCORE", game.home_team.score.to_string()),
+        ("NBA_STATUS", game.status.to_string()),
+    ];
+    if let EventKind::PeriodEnded(period) = event.kind {
+        variables.push(("NBA_PERIOD", period.to_string()));
+    }
+    return variables;
+}
@@ -1,7 +1,44 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use select::document::Document;
 use select::predicate::{Class, Name, Predicate};
 
 use crate::team::Team;
-use crate::game::{Game, create_nonstarted_game};
+use crate::game::{Game, GameInfo, create_nonstarted_game};
+use crate::game_status::GameStatus;
+use crate::timezones::{format_start, parse_eastern_start};
+
+/// Everything that can go wrong while reading a single game block. A game that fails to parse is
+/// reported on its own instead of taking the whole scoreboard down with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The game block does not contain the names of both teams
+    MissingTeamNames,
+    /// A score cell holds something other than a number
+    NonNumericScore(String),
+    /// A game leader line is not in the form "<player name> <value>"
+    MalformedLeader(String),
+    /// Neither the game status nor the start time could be found
+    MissingGameTime,
+    /// The game status is not one we know how to read
+    UnknownStatus(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingTeamNames => write!(f, "missing team names"),
+            ParseError::NonNumericScore(score) => write!(f, "score '{}' is not a number", score),
+            ParseError::MalformedLeader(line) => write!(f, "malformed game leader '{}'", line),
+            ParseError::MissingGameTime => write!(f, "missing game time"),
+            ParseError::UnknownStatus(status) => write!(f, "unknown game status '{}'", status),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
 
 /// Parses a game block document node to retrieve the two team names.
 ///
@@ -12,19 +49,148 @@ use crate::game::{Game, create_nonstarted_game};
 /// # Examples
 ///
 /// ```
-/// let (home_team_name, away_team_name) = get_team_names(game_block);
+/// let (home_team_name, away_team_name) = get_team_names(game_block)?;
 /// ```
-fn get_team_names(game_block: select::node::Node) -> (String, String) {
+fn get_team_names(game_block: select::node::Node) -> Result<(String, String), ParseError> {
     let teams: Vec<String> = game_block
         .find(Class("shsNamD").descendant(Name("a")))
         .map(|tag| tag.text())
         .collect::<Vec<String>>();
 
-    // TODO: panicking here when (I'm assuming) we don't have an HTML document to actually parse --
-    // it failed to read the site
-    let away_team_name = String::from(teams.get(0).expect("No team names in this vector"));
-    let home_team_name = String::from(teams.get(1).expect("No team names in this vector"));
-    return (home_team_name, away_team_name);
+    let away_team_name = String::from(teams.first().ok_or(ParseError::MissingTeamNames)?);
+    let home_team_name = String::from(teams.get(1).ok_or(ParseError::MissingTeamNames)?);
+    return Ok((home_team_name, away_team_name));
+}
+
+/// Reads the line score grid of a game block. The grid is formatted like this:
+/// 1 2 3 4 Tot
+/// _ _ _ _ ___
+/// _ _ _ _ ___
+/// where the underscores represent values scored in the 1st, 2nd, 3rd, and 4th quarters (plus one
+/// column per overtime, if any) as well as the whole game -- labeled by the header row
+///
+/// Returns the number of columns in each row and all of the cells, header row first, then the away
+/// and home rows.
+fn get_score_grid(game_block: select::node::Node) -> (usize, Vec<String>) {
+    let scores: Vec<String> = game_block
+        .find(Class("shsTotD"))
+        .map(|tag| tag.text())
+        .collect::<Vec<String>>();
+
+    // the header row ends with the "Tot" column, so that is where the first row stops. Overtimes
+    // add columns, so the rows can't be assumed to be 5 wide
+    let n_cols = scores
+        .iter()
+        .position(|cell| cell.trim().eq_ignore_ascii_case("tot"))
+        .map(|index| index + 1)
+        .unwrap_or(scores.len() / 3);
+    return (n_cols, scores);
+}
+
+/// Parses a game block document node to retrieve the status of the game. Games that haven't
+/// started have no status text, only their start time.
+///
+/// # Arguments
+///
+/// * `game_block` - The Node (from select.rs) of a game, holding its status and, before tip-off,
+///   its start times
+/// * `date` - The day of the scoreboard the game is on
+///
+/// # Examples
+///
+/// ```
+/// let status = get_game_status(game_block, date)?;
+/// ```
+fn get_game_status(game_block: select::node::Node, date: NaiveDate) -> Result<GameStatus, ParseError> {
+    // the status is unfortunately a different html tag if the game hasn't started yet
+    let status_text = game_block
+        .find(Class("shsTeamCol"))
+        .next()
+        .map(|tag| tag.text())
+        .unwrap_or_default();
+
+    match GameStatus::parse(&status_text) {
+        // a game delayed once under way has its scores so far
+        Ok(GameStatus::Delayed { .. }) => Ok(GameStatus::Delayed { started: has_scores(game_block) }),
+        Ok(status) => Ok(status),
+        // no status we can read and no scores yet -- the game simply hasn't started
+        Err(_) if !has_scores(game_block) => Ok(GameStatus::Scheduled {
+            start: get_start_time(game_block, date)?,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+/// Parses a game block document node to retrieve the start time of a game that hasn't started, in
+/// the time zone the program is set to. The page lists it in each of the US time zones; the
+/// Eastern one is converted, or shown as it is if it isn't a time ("TBD").
+///
+/// # Arguments
+///
+/// * `game_block` - The Node (from select.rs) of a game that hasn't started, holding its start times
+/// * `date` - The day of the scoreboard the game is on
+///
+/// # Examples
+///
+/// ```
+/// let start = get_start_time(game_block, date)?;
+/// ```
+fn get_start_time(game_block: select::node::Node, date: NaiveDate) -> Result<String, ParseError> {
+    let eastern = get_eastern_start(game_block)?;
+    match parse_eastern_start(date, &eastern) {
+        Some(start) => Ok(format_start(start, date)),
+        None => Ok(String::from(eastern.trim())),
+    }
+}
+
+/// The start time of a game that hasn't started as the page writes it in US Eastern time
+fn get_eastern_start(game_block: select::node::Node) -> Result<String, ParseError> {
+    // the start time is listed in Pacific, Mountain, Central and Eastern time
+    return game_block
+        .find(Class("shsTimezone"))
+        .map(|tag| tag.text())
+        .nth(3)
+        .ok_or(ParseError::MissingGameTime);
+}
+
+/// Parses a game block document node to retrieve the details about a game that the page only
+/// sometimes offers: start time (shown and as a point in time), venue and broadcast.
+///
+/// # Arguments
+///
+/// * `game_block` - The Node (from select.rs) of a game, holding whichever details the page has
+/// * `date` - The day of the scoreboard the game is on
+///
+/// # Examples
+///
+/// ```
+/// let info = get_game_info(game_block, date);
+/// ```
+fn get_game_info(game_block: select::node::Node, date: NaiveDate) -> GameInfo {
+    // the text of the first tag of the class, if there is one with any text
+    let find_text = |class: &str| {
+        game_block
+            .find(Class(class))
+            .next()
+            .map(|tag| String::from(tag.text().trim()))
+            .filter(|text| !text.is_empty())
+    };
+    return GameInfo {
+        start_time: get_start_time(game_block, date).ok(),
+        start: get_eastern_start(game_block).ok().and_then(|eastern| parse_eastern_start(date, &eastern)),
+        venue: find_text("shsVenue"),
+        broadcast: find_text("shsTV"),
+    };
+}
+
+/// True if the line score grid of the game block holds any score at all
+fn has_scores(game_block: select::node::Node) -> bool {
+    let (n_cols, scores) = get_score_grid(game_block);
+    // skip the header row
+    return scores
+        .iter()
+        .skip(n_cols)
+        .any(|cell| !cell.trim().is_empty());
 }
 
 /// Parses a game block document node to retrieve two team scores.
@@ -36,41 +202,56 @@ fn get_team_names(game_block: select::node::Node) -> (String, String) {
 /// # Examples
 ///
 /// ```
-/// let (home_score, away_score) = get_team_scores(game_block);
+/// let (home_score, away_score) = get_team_scores(game_block)?;
 /// ```
-fn get_team_scores(game_block: select::node::Node) -> (u32, u32) {
-    // there are 15 values in here formatted like this:
-    // 1 2 3 4 Tot
-    // _ _ _ _ ___
-    // _ _ _ _ ___
-    // where the underscores represent values scored in the 1st, 2nd, 3rd, and 4th quarters
-    // as well as the whole game -- labeled by the header row
-    let scores: Vec<String> = game_block
-        .find(Class("shsTotD"))
-        .map(|tag| tag.text())
-        .collect::<Vec<String>>();
+fn get_team_scores(game_block: select::node::Node) -> Result<(u32, u32), ParseError> {
+    let (n_cols, scores) = get_score_grid(game_block);
+    let away_score = parse_score((n_cols * 2).checked_sub(1).and_then(|i| scores.get(i)))?;
+    let home_score = parse_score((n_cols * 3).checked_sub(1).and_then(|i| scores.get(i)))?;
+
+    return Ok((home_score, away_score));
+}
 
-    // Critical design choice here: We decide that if there are no scores found (there are 10
-    // whitespace regions), then we return the max u32 value
-    if scores.len() == 10 {
-        return (u32::MAX, u32::MAX);
+/// Parses a game block document node to retrieve the points each team scored in every period
+/// played so far (quarters, then overtimes).
+///
+/// # Arguments
+///
+/// * `game_block` - The Node (from select.rs) of a game, holding its table of period scores
+///
+/// # Examples
+///
+/// ```
+/// let (home_periods, away_periods) = get_period_scores(game_block)?;
+/// ```
+fn get_period_scores(game_block: select::node::Node) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    let (n_cols, scores) = get_score_grid(game_block);
+    if n_cols == 0 {
+        return Ok((Vec::new(), Vec::new()));
     }
 
+    // every row but the last ("Tot") column
+    let away_row = scores.get(n_cols..n_cols * 2 - 1).unwrap_or(&[]);
+    let home_row = scores.get(n_cols * 2..n_cols * 3 - 1).unwrap_or(&[]);
+    return Ok((parse_periods(home_row)?, parse_periods(away_row)?));
+}
 
-    // there are scores shown -- continue
-    let n_cols = scores.len() / 3;
-    let away_score = scores
-        .get(n_cols * 2 - 1)
-        .expect("Could not get away team's score")
-        .parse::<u32>()
-        .unwrap();
-    let home_score = scores
-        .get(n_cols * 3 - 1)
-        .expect("Could not get home team's score")
-        .parse::<u32>()
-        .unwrap();
+/// Reads the scores of a line score row, stopping at the first period that hasn't been played yet
+/// (an empty cell)
+fn parse_periods(row: &[String]) -> Result<Vec<u32>, ParseError> {
+    return row
+        .iter()
+        .take_while(|cell| !cell.trim().is_empty())
+        .map(|cell| parse_score(Some(cell)))
+        .collect();
+}
 
-    return (home_score, away_score);
+/// Reads the number out of a score cell, a missing cell is reported as an empty score
+fn parse_score(cell: Option<&String>) -> Result<u32, ParseError> {
+    let text = cell.map(|score| score.trim()).unwrap_or("");
+    return text
+        .parse::<u32>()
+        .map_err(|_| ParseError::NonNumericScore(String::from(text)));
 }
 
 /// Parses the HTML game block region and populates the given vectors with Names and values of game
@@ -79,7 +260,7 @@ fn get_team_scores(game_block: select::node::Node) -> (u32, u32) {
 /// # Arguments
 ///
 /// * `game_block` - The HTML region representing the game containing game leaders in points,
-/// rebounds and assists
+///   rebounds and assists
 /// * `home_leader_names` - The empty vector to populate game leader names for the home team
 /// * `home_leader_values` - The empty vector to populate game leader values for the home team
 /// * `away_leader_names` - The empty vector to populate game leader names for the away team
@@ -95,11 +276,11 @@ fn get_team_scores(game_block: select::node::Node) -> (u32, u32) {
 /// let mut away_leader_values: Vec<u32> = Vec::new();
 ///
 /// // populate the vectores defined above.
-/// get_game_leaders(game_block, &mut home_leader_names, &mut home_leader_values, &mut away_leader_names, &mut away_leader_values);
+/// get_game_leaders(game_block, &mut home_leader_names, &mut home_leader_values, &mut away_leader_names, &mut away_leader_values)?;
 /// ```
 fn get_game_leaders(game_block: select::node::Node,
     home_leader_names: &mut Vec<String>, home_leader_values: &mut Vec<u32>,
-    away_leader_names: &mut Vec<String>, away_leader_values: &mut Vec<u32>) {
+    away_leader_names: &mut Vec<String>, away_leader_values: &mut Vec<u32>) -> Result<(), ParseError> {
     // get team leaders from html
     let stat_leaders_raw: Vec<String> = game_block
         .find(Class("shsLeader"))
@@ -109,18 +290,18 @@ fn get_game_leaders(game_block: select::node::Node,
     // store the stat leader names and the values of those stats
 
     // there will be 6 values in this
-    let mut counter = 0;
-    for val in stat_leaders_raw {
-        let val_as_str = String::from(val);
-        let val_split_by_whitespace = val_as_str.split_whitespace().collect::<Vec<&str>>();
+    for (counter, val) in stat_leaders_raw.iter().enumerate() {
+        let val_split_by_whitespace = val.split_whitespace().collect::<Vec<&str>>();
+        // a leader needs at least a name and a value
+        if val_split_by_whitespace.len() < 2 {
+            return Err(ParseError::MalformedLeader(val.clone()));
+        }
         // form player name from every value but the final string
         let player_name = &val_split_by_whitespace[..val_split_by_whitespace.len() - 1].join(" ");
         // final string in vector represents the value of the stat category
-        let number = val_split_by_whitespace
-            .last()
-            .expect("Can't retrieve last value in array")
+        let number = val_split_by_whitespace[val_split_by_whitespace.len() - 1]
             .parse::<u32>()
-            .unwrap();
+            .map_err(|_| ParseError::MalformedLeader(val.clone()))?;
         if counter % 2 == 1 {
             // home team values
             home_leader_names.push(player_name.clone());
@@ -130,9 +311,8 @@ fn get_game_leaders(game_block: select::node::Node,
             away_leader_names.push(player_name.clone());
             away_leader_values.push(number);
         }
-        counter += 1;
-        // stat_leaders_raw is freed
     }
+    return Ok(());
 }
 
 // Public functions
@@ -143,6 +323,7 @@ fn get_game_leaders(game_block: select::node::Node,
 /// # Arguments
 ///
 /// * `game_block` - A Node (from select.rs) object containing tags with
+/// * `date` - The day of the scoreboard the game is on
 ///
 /// # Examples
 ///
@@ -150,52 +331,197 @@ fn get_game_leaders(game_block: select::node::Node,
 /// // retrieve a "game block" from a html document
 /// let document = Document::from(&*resp.text().await?);
 /// let game_block = document.find(Class("shsScoreboardRow")).find(Class("shsScoreboardCol"));
-/// let game: Game = form_game(game_block);
+/// let game: Game = form_game(game_block, date)?;
 /// // To display the game
-/// game.display();
+/// game.display(&mut stdout(), &DisplayOptions::default())?;
 /// ```
-pub fn form_game(game_block: select::node::Node) -> Game {
+pub fn form_game(game_block: select::node::Node, date: NaiveDate) -> Result<Game, ParseError> {
     // Does all the html parsing to make teams
-    let (home_team_name, away_team_name) = get_team_names(game_block);
-    let (home_score, away_score) = get_team_scores(game_block);
+    let (home_team_name, away_team_name) = get_team_names(game_block)?;
+    let status = get_game_status(game_block, date)?;
+    let info = get_game_info(game_block, date);
+
+    // scores and leaders only exist once the game has started
+    if !status.has_started() {
+        return Ok(create_nonstarted_game(&home_team_name, &away_team_name, status, info));
+    }
+
+    let (home_score, away_score) = get_team_scores(game_block)?;
+    let (home_periods, away_periods) = get_period_scores(game_block)?;
     let mut home_leader_names: Vec<String> = Vec::new();
     let mut away_leader_names: Vec<String> = Vec::new();
     let mut home_leader_values: Vec<u32> = Vec::new();
     let mut away_leader_values: Vec<u32> = Vec::new();
 
-    get_game_leaders(game_block, &mut home_leader_names, &mut home_leader_values, &mut away_leader_names, &mut away_leader_values);
+    get_game_leaders(game_block, &mut home_leader_names, &mut home_leader_values, &mut away_leader_names, &mut away_leader_values)?;
     // Done parsing html
 
-    // NOTE: This is a check for if the game has started yet or not
-    // The get_team_scores function returns max u32 value if the game has yet to start
-    if home_score == u32::MAX && away_score == u32::MAX {
-        let game = create_nonstarted_game(&home_team_name, &away_team_name, game_block);
-        return game;
-    }
-
-    // scrape gametime -- this is unfortunately a different html tag if the game hasn't started yet
-    let game_time = game_block.find(Class("shsTeamCol")).next().unwrap().text();
-
     // Instantiate teams from the values we just scraped
     let home_team = Team::from_leader_vector(
         home_team_name,
         home_score,
+        home_periods,
         home_leader_names,
         home_leader_values
     );
     let away_team = Team::from_leader_vector(
         away_team_name,
         away_score,
+        away_periods,
         away_leader_names,
         away_leader_values
     );
 
     let game = Game {
-        has_started: true,
+        status,
         away_team,
         home_team,
-        game_time,
+        info,
     };
 
-    return game;
+    return Ok(game);
+}
+
+/// Parses a whole scoreboard page and forms a Game for every game block found on it. Game blocks
+/// that could not be parsed are kept (as errors) in their place on the scoreboard.
+///
+/// # Arguments
+///
+/// * `document` - The scoreboard HTML document
+/// * `date` - The day of the scoreboard
+///
+/// # Examples
+///
+/// ```
+/// let document = Document::from(&*resp.text().await?);
+/// for game in parse_scoreboard(&document, date).into_iter().flatten() {
+///     game.display(&mut stdout(), &DisplayOptions::default())?;
+/// }
+/// ```
+pub fn parse_scoreboard(document: &Document, date: NaiveDate) -> Vec<Result<Game, ParseError>> {
+    let mut games: Vec<Result<Game, ParseError>> = Vec::new();
+    for row in document.find(Class("shsScoreboardRow")) {
+        // there are two games per row
+        for game_block in row.find(Class("shsScoreboardCol")) {
+            // given a game block, form two Teams and a Game
+            games.push(form_game(game_block, date));
+        }
+    }
+    return games;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game block holding only a line score grid: the header, away and home rows
+    fn grid_document(rows: [&[&str]; 3]) -> Document {
+        let rows: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let cells: String = row.iter().map(|cell| format!("<td class=\"shsTotD\">{}</td>", cell)).collect();
+                format!("<tr>{}</tr>", cells)
+            })
+            .collect();
+        let html = format!("<div class=\"shsScoreboardCol\"><table>{}</table></div>", rows.concat());
+        return Document::from(html.as_str());
+    }
+
+    fn game_block(document: &Document) -> select::node::Node<'_> {
+        return document.find(Class("shsScoreboardCol")).next().unwrap();
+    }
+
+    #[test]
+    fn reads_a_regulation_grid() {
+        let document = grid_document([
+            &["1", "2", "3", "4", "Tot"],
+            &["30", "25", "28", "27", "110"],
+            &["20", "28", "15", "30", "93"],
+        ]);
+        let block = game_block(&document);
+        assert_eq!(get_score_grid(block).0, 5);
+        assert_eq!(get_team_scores(block).unwrap(), (93, 110));
+        assert_eq!(get_period_scores(block).unwrap(), (vec![20, 28, 15, 30], vec![30, 25, 28, 27]));
+        assert!(has_scores(block));
+    }
+
+    #[test]
+    fn reads_a_grid_with_overtimes() {
+        let document = grid_document([
+            &["1", "2", "3", "4", "OT", "2OT", "Tot"],
+            &["30", "25", "28", "17", "10", "12", "122"],
+            &["20", "28", "25", "27", "10", "9", "119"],
+        ]);
+        let block = game_block(&document);
+        assert_eq!(get_score_grid(block).0, 7);
+        assert_eq!(get_team_scores(block).unwrap(), (119, 122));
+        assert_eq!(get_period_scores(block).unwrap(), (vec![20, 28, 25, 27, 10, 9], vec![30, 25, 28, 17, 10, 12]));
+    }
+
+    #[test]
+    fn reads_a_grid_being_played() {
+        let document = grid_document([
+            &["1", "2", "3", "4", "Tot"],
+            &["30", "25", " ", " ", "55"],
+            &["20", "28", " ", " ", "48"],
+        ]);
+        let block = game_block(&document);
+        assert_eq!(get_team_scores(block).unwrap(), (48, 55));
+        // the periods stop at the first one not played yet
+        assert_eq!(get_period_scores(block).unwrap(), (vec![20, 28], vec![30, 25]));
+    }
+
+    #[test]
+    fn reads_a_blank_grid() {
+        let document = grid_document([
+            &[" ", " ", " ", " ", " "],
+            &[" ", " ", " ", " ", " "],
+            &[" ", " ", " ", " ", " "],
+        ]);
+        let block = game_block(&document);
+        assert!(!has_scores(block));
+        assert_eq!(get_period_scores(block).unwrap(), (vec![], vec![]));
+        assert_eq!(get_team_scores(block), Err(ParseError::NonNumericScore(String::new())));
+    }
+
+    #[test]
+    fn reads_a_grid_without_a_total_header() {
+        // without "Tot" the cells are split in three rows of the same width
+        let document = grid_document([
+            &["1", "2", "3", "4", "OT", ""],
+            &["30", "25", "28", "17", "10", "110"],
+            &["20", "28", "25", "27", "8", "108"],
+        ]);
+        let block = game_block(&document);
+        assert_eq!(get_score_grid(block).0, 6);
+        assert_eq!(get_team_scores(block).unwrap(), (108, 110));
+        assert_eq!(get_period_scores(block).unwrap(), (vec![20, 28, 25, 27, 8], vec![30, 25, 28, 17, 10]));
+    }
+
+    #[test]
+    fn keeps_the_scores_of_delayed_games() {
+        let html = "<div class=\"shsScoreboardCol\"><table>\
+            <tr><td class=\"shsTeamCol\">Delayed</td><td class=\"shsTotD\">1</td><td class=\"shsTotD\">Tot</td></tr>\
+            <tr><td class=\"shsNamD\"><a>Utah</a></td><td class=\"shsTotD\">2</td><td class=\"shsTotD\">2</td></tr>\
+            <tr><td class=\"shsNamD\"><a>Denver</a></td><td class=\"shsTotD\">0</td><td class=\"shsTotD\">0</td></tr>\
+            </table></div>";
+        let document = Document::from(html);
+        let date = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        let game = form_game(game_block(&document), date).unwrap();
+        assert_eq!(game.status, GameStatus::Delayed { started: true });
+        assert_eq!((game.away_team.score, game.home_team.score), (2, 0));
+        assert_eq!(game.away_team.period_scores, vec![2]);
+    }
+
+    #[test]
+    fn rejects_non_numeric_scores() {
+        let document = grid_document([
+            &["1", "2", "3", "4", "Tot"],
+            &["30", "2S", " ", " ", "55"],
+            &["20", "28", " ", " ", "--"],
+        ]);
+        let block = game_block(&document);
+        assert_eq!(get_team_scores(block), Err(ParseError::NonNumericScore(String::from("--"))));
+        assert_eq!(get_period_scores(block), Err(ParseError::NonNumericScore(String::from("2S"))));
+    }
 }
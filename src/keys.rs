@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use termion::event::Key;
+
+use crate::config::{ConfigError, KeyList};
+
+/// Everything a key can do in the live scoreboard. In the config file actions are written in
+/// snake case (`toggle_leaders`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    Refresh,
+    PreviousDay,
+    NextDay,
+    Today,
+    Up,
+    Down,
+    Open,
+    Back,
+    ToggleLeaders,
+    ToggleLinescore,
+    Filter,
+    FavoritesOnly,
+    Sort,
+    Help,
+}
+
+impl Action {
+    /// Every action, in the order they are listed in the help
+    pub const ALL: [Action; 15] = [
+        Action::PreviousDay,
+        Action::NextDay,
+        Action::Today,
+        Action::Up,
+        Action::Down,
+        Action::Open,
+        Action::Back,
+        Action::Refresh,
+        Action::ToggleLeaders,
+        Action::ToggleLinescore,
+        Action::Filter,
+        Action::FavoritesOnly,
+        Action::Sort,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// What the action does, as shown in the help
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Refresh => "refresh now",
+            Action::PreviousDay => "previous day",
+            Action::NextDay => "next day",
+            Action::Today => "back to today",
+            Action::Up => "select the game above",
+            Action::Down => "select the game below",
+            Action::Open => "open the detail page of the selected game",
+            Action::Back => "back to the scoreboard",
+            Action::ToggleLeaders => "show or hide the game leaders",
+            Action::ToggleLinescore => "show or hide the line scores",
+            Action::Filter => "show only the games being played, or every game",
+            Action::FavoritesOnly => "show only the games of favorite teams, or every game",
+            Action::Sort => "sort the games by favorites, start time, status or closeness",
+            Action::Help => "show or hide this help",
+        }
+    }
+
+    /// Names of the keys bound to the action when the config file doesn't say otherwise
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q"],
+            Action::Refresh => &["r"],
+            Action::PreviousDay => &["left", "p"],
+            Action::NextDay => &["right", "n"],
+            Action::Today => &["t"],
+            Action::Up => &["up", "k"],
+            Action::Down => &["down", "j"],
+            Action::Open => &["enter"],
+            Action::Back => &["esc"],
+            Action::ToggleLeaders => &["l"],
+            Action::ToggleLinescore => &["b"],
+            Action::Filter => &["i"],
+            Action::FavoritesOnly => &["f"],
+            Action::Sort => &["s"],
+            Action::Help => &["?"],
+        }
+    }
+}
+
+/// Which action each key triggers
+pub struct KeyBindings {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl KeyBindings {
+    /// Builds the key bindings from the `[keys]` of the config file. An action listed there only
+    /// has the keys it is given; the others keep their default keys, unless one of them was given
+    /// to another action.
+    ///
+    /// # Arguments
+    ///
+    /// * `remapped` - The keys of each action listed in the config file
+    pub fn new(remapped: &HashMap<Action, KeyList>) -> Result<KeyBindings, ConfigError> {
+        let mut bindings: Vec<(Key, Action)> = Vec::new();
+        for action in Action::ALL {
+            if let Some(keys) = remapped.get(&action) {
+                for name in keys.names() {
+                    let key = parse_key(name).ok_or_else(|| ConfigError::UnknownKey(name.clone()))?;
+                    bindings.push((key, action));
+                }
+            }
+        }
+        for action in Action::ALL {
+            if remapped.contains_key(&action) {
+                continue;
+            }
+            for name in action.default_keys() {
+                // a default key can't be missing, its name comes from the list above
+                let key = parse_key(name).unwrap();
+                if bindings.iter().all(|(bound, _)| *bound != key) {
+                    bindings.push((key, action));
+                }
+            }
+        }
+        return Ok(KeyBindings { bindings });
+    }
+
+    /// Returns the action of a key press, if the key is bound to one
+    pub fn action(&self, key: Key) -> Option<Action> {
+        return self
+            .bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action);
+    }
+
+    /// Lists every action with the names of its keys, for the help
+    pub fn help(&self) -> Vec<(Action, Vec<String>)> {
+        return Action::ALL
+            .iter()
+            .map(|action| {
+                let keys = self
+                    .bindings
+                    .iter()
+                    .filter(|(_, bound)| bound == action)
+                    .map(|(key, _)| key_name(*key))
+                    .collect();
+                (*action, keys)
+            })
+            .collect();
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> KeyBindings {
+        // no key was remapped, there is nothing that can fail
+        return KeyBindings::new(&HashMap::new()).unwrap();
+    }
+}
+
+/// Turns the name of a key in the config file into the key: a single character ("q", "?"), a named
+/// key ("left", "enter", "esc", "space", "f5", ...) or a character with a modifier ("ctrl-c",
+/// "alt-x"). Names are not case sensitive, except for single characters.
+pub fn parse_key(name: &str) -> Option<Key> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Key::Char(c));
+    }
+    let lowercase = name.to_lowercase();
+    if let Some(c) = lowercase.strip_prefix("ctrl-").and_then(single_char) {
+        return Some(Key::Ctrl(c));
+    }
+    if let Some(c) = lowercase.strip_prefix("alt-").and_then(single_char) {
+        return Some(Key::Alt(c));
+    }
+    if let Some(number) = lowercase.strip_prefix('f').and_then(|number| number.parse::<u8>().ok()) {
+        return if (1..=12).contains(&number) { Some(Key::F(number)) } else { None };
+    }
+    let key = match lowercase.as_str() {
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "backspace" => Key::Backspace,
+        "delete" => Key::Delete,
+        "insert" => Key::Insert,
+        "esc" => Key::Esc,
+        // termion reads these keys as characters
+        "enter" => Key::Char('\n'),
+        "tab" => Key::Char('\t'),
+        "space" => Key::Char(' '),
+        _ => return None,
+    };
+    return Some(key);
+}
+
+/// The character of a string that is exactly one character long
+fn single_char(text: &str) -> Option<char> {
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Name of a key as shown in the help
+pub fn key_name(key: Key) -> String {
+    match key {
+        Key::Char('\n') => String::from("Enter"),
+        Key::Char('\t') => String::from("Tab"),
+        Key::Char(' ') => String::from("Space"),
+        Key::Char(c) => c.to_string(),
+        Key::Ctrl(c) => format!("Ctrl-{}", c),
+        Key::Alt(c) => format!("Alt-{}", c),
+        Key::F(number) => format!("F{}", number),
+        Key::Left => String::from("←"),
+        Key::Right => String::from("→"),
+        Key::Up => String::from("↑"),
+        Key::Down => String::from("↓"),
+        Key::Home => String::from("Home"),
+        Key::End => String::from("End"),
+        Key::PageUp => String::from("PageUp"),
+        Key::PageDown => String::from("PageDown"),
+        Key::Backspace => String::from("Backspace"),
+        Key::Delete => String::from("Delete"),
+        Key::Insert => String::from("Insert"),
+        Key::Esc => String::from("Esc"),
+        _ => format!("{:?}", key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remap(keys: &[(Action, &[&str])]) -> HashMap<Action, KeyList> {
+        return keys
+            .iter()
+            .map(|(action, names)| (*action, KeyList::Many(names.iter().map(|name| name.to_string()).collect())))
+            .collect();
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(parse_key("q"), Some(Key::Char('q')));
+        // single characters keep their case, names don't
+        assert_eq!(parse_key("Q"), Some(Key::Char('Q')));
+        assert_eq!(parse_key("?"), Some(Key::Char('?')));
+        assert_eq!(parse_key("Left"), Some(Key::Left));
+        assert_eq!(parse_key("PAGEDOWN"), Some(Key::PageDown));
+        assert_eq!(parse_key("enter"), Some(Key::Char('\n')));
+        assert_eq!(parse_key("space"), Some(Key::Char(' ')));
+        assert_eq!(parse_key("ctrl-c"), Some(Key::Ctrl('c')));
+        assert_eq!(parse_key("Alt-X"), Some(Key::Alt('x')));
+        assert_eq!(parse_key("f5"), Some(Key::F(5)));
+        assert_eq!(parse_key("F12"), Some(Key::F(12)));
+    }
+
+    #[test]
+    fn rejects_unknown_key_names() {
+        assert_eq!(parse_key(""), None);
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("f0"), None);
+        assert_eq!(parse_key("ctrl-"), None);
+        assert_eq!(parse_key("ctrl-cc"), None);
+        assert_eq!(parse_key("shift-a"), None);
+        assert_eq!(parse_key("return"), None);
+    }
+
+    #[test]
+    fn binds_default_keys() {
+        let keys = KeyBindings::default();
+        assert_eq!(keys.action(Key::Char('q')), Some(Action::Quit));
+        assert_eq!(keys.action(Key::Left), Some(Action::PreviousDay));
+        assert_eq!(keys.action(Key::Char('p')), Some(Action::PreviousDay));
+        assert_eq!(keys.action(Key::Char('\n')), Some(Action::Open));
+        assert_eq!(keys.action(Key::Char('x')), None);
+    }
+
+    #[test]
+    fn remapped_actions_only_have_their_keys() {
+        let keys = KeyBindings::new(&remap(&[(Action::Quit, &["x", "ctrl-c"])])).unwrap();
+        assert_eq!(keys.action(Key::Char('x')), Some(Action::Quit));
+        assert_eq!(keys.action(Key::Ctrl('c')), Some(Action::Quit));
+        assert_eq!(keys.action(Key::Char('q')), None);
+        // the other actions keep their defaults
+        assert_eq!(keys.action(Key::Char('r')), Some(Action::Refresh));
+    }
+
+    #[test]
+    fn remapped_keys_win_over_default_keys() {
+        // "n" is the default key of the next day, given to the leaders here
+        let keys = KeyBindings::new(&remap(&[(Action::ToggleLeaders, &["n"])])).unwrap();
+        assert_eq!(keys.action(Key::Char('n')), Some(Action::ToggleLeaders));
+        assert_eq!(keys.action(Key::Right), Some(Action::NextDay));
+        assert_eq!(keys.action(Key::Char('l')), None);
+        let help = keys.help();
+        let next_day = help.iter().find(|(action, _)| *action == Action::NextDay).unwrap();
+        assert_eq!(next_day.1, vec![String::from("→")]);
+    }
+
+    #[test]
+    fn rejects_unknown_keys_in_the_config() {
+        let remapped = remap(&[(Action::Refresh, &["r", "f13"])]);
+        assert!(matches!(KeyBindings::new(&remapped), Err(ConfigError::UnknownKey(name)) if name == "f13"));
+    }
+}
@@ -1,138 +1,270 @@
+// explicit returns are the style used throughout this program
+#![allow(clippy::needless_return)]
+
 // external packages
 extern crate reqwest;
 extern crate termion;
 
-use std::{thread, time};
-use chrono;
+use chrono::NaiveDate;
+use chrono_tz::Tz;
 use clap::Parser;
-use select::document::Document;
-use select::predicate::Class;
-
-
-use termion::event::Key;
-use termion::input::TermRead;
-use termion::raw::IntoRawMode;
-use termion::async_stdin;
 
-
-//use tokio::io::stdout;
-use std::io::{Read, Write, stdout, stdin};
+use std::io::stdout;
 use std::panic;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 // internal packages
-mod constants;
+mod app;
+mod colors;
+mod config;
 mod date_handler;
 mod display;
+mod events;
+mod fixture_provider;
 mod game;
+mod game_status;
+mod hooks;
 mod html_parser;
+mod keys;
+mod output;
+mod provider;
+mod recorder;
+mod refresh;
+mod sort;
 mod team;
+mod teams;
 mod timezones;
 
-use crate::date_handler::extract_date_argument;
-use crate::display::print_header;
-use crate::game::Game;
-use crate::display::{clear_terminal, cleanup_terminal};
-use crate::html_parser::form_game;
+use crate::app::App;
+use crate::colors::{set_color_depth, use_color, ColorChoice, ColorDepth};
+use crate::config::Config;
+use crate::date_handler::{extract_date_argument, extract_date_range, DateError};
+use crate::display::{print_date_range, print_day, DisplayOptions};
+use crate::display::cleanup_terminal;
+use crate::fixture_provider::FixtureProvider;
+use crate::keys::KeyBindings;
+use crate::output::{watch_ndjson, write_scoreboard, write_scoreboards, OutputFormat};
+use crate::provider::{create_provider, fetch_scoreboards, ProviderError, ProviderKind, ScoreProvider, TeamFilter};
+use crate::recorder::Recorder;
+use crate::refresh::RefreshPolicy;
+use crate::sort::SortOrder;
+use crate::teams::{parse_team, NameStyle, TeamInfo};
+use crate::timezones::set_time_zone;
 
 // TODO:
 // * Major refactoring of form_game function
 // * down side of making team.rs public??
 // * Implement Scraping module that scrapes the relevant information from the site
-// * How to display and format game leaders info
-// * Team name colors
-// * A class that handles making dates look human readable and interprettable to the program
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// Date of games to retrieve. must be in YYYYMMDD format.
+    /// Date of games to retrieve: YYYYMMDD, YYYY-MM-DD, MM/DD, days from today (-3, +2), a weekday
+    /// ("sat", "last friday", "next mon") or a name from the season calendar ("opening-night").
     /// Shortcuts:
     /// t: today
     /// T: tomorrow
     /// y: yesterday
-    #[clap(short, long, default_value = "t")]
+    /// A range of days is given as <from>..<to>, e.g. 20261010..20261016
+    #[clap(short, long, default_value = "t", allow_hyphen_values = true)]
     date: String,
+
+    /// First date of a range of days to show at once (any format --date accepts)
+    #[clap(long, allow_hyphen_values = true)]
+    from: Option<String>,
+
+    /// Last date of the range started with --from (defaults to today)
+    #[clap(long, requires = "from", allow_hyphen_values = true)]
+    to: Option<String>,
+
+    /// Website to retrieve the scores from
+    #[clap(short, long, value_enum, default_value = "nbc-sports")]
+    provider: ProviderKind,
+
+    /// How to write the scoreboard: the live table, JSON for scripts (json: once and exit, ndjson:
+    /// one line per refresh until every game is over) or CSV/TSV for spreadsheets (once and exit)
+    #[clap(short, long, value_enum, default_value = "table")]
+    format: OutputFormat,
+
+    /// When to color the output: auto colors it on a terminal unless NO_COLOR is set
+    #[clap(long, value_enum, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Colors the terminal can show, team colors are matched to the closest ones. Auto guesses
+    /// from the COLORTERM and TERM environment variables
+    #[clap(long, value_enum, default_value = "auto")]
+    color_depth: ColorDepth,
+
+    /// Print the scoreboard once as plain text, without colors or terminal codes (the default when
+    /// the output is not a terminal)
+    #[clap(long)]
+    plain: bool,
+
+    /// Only show the games of these teams, given by abbreviation, city or nickname and separated
+    /// by commas: LAL,BOS or "Lakers,Boston"
+    #[clap(long, value_delimiter = ',', value_parser = parse_team)]
+    team: Vec<&'static TeamInfo>,
+
+    /// Order of the games on the scoreboard: start (as the page lists them), status (being played
+    /// first, finished last), closeness (like status, closest games first) or favorites (games of
+    /// the favorite teams of the config file first). Cycle through them with 's'
+    #[clap(short, long, value_enum, default_value = "favorites")]
+    sort: SortOrder,
+
+    /// How to write team names: abbr ("LAL"), short ("LA Lakers") or full ("Los Angeles Lakers")
+    #[clap(short, long, value_enum, default_value = "short")]
+    names: NameStyle,
+
+    /// Time zone to show start times in, as an IANA name ("Europe/London", "America/Chicago").
+    /// Defaults to the zone of the system (the TZ environment variable, then the system settings)
+    #[clap(long)]
+    tz: Option<Tz>,
+
+    /// Print the quarter by quarter line score under each game
+    #[clap(long)]
+    linescore: bool,
+
+    /// Print the points, rebounds and assists leaders under each game (toggle with 'l')
+    #[clap(long)]
+    leaders: bool,
+
+    /// Seconds between refreshes of the scoreboard, or "adaptive" to refresh every few seconds while
+    /// games are being played, every few minutes while they have yet to start and not at all once
+    /// every game is over
+    #[clap(short, long, default_value = "10")]
+    interval: RefreshPolicy,
+
+    /// Ring the terminal bell when something happens in a game: tip-off, end of a period, lead
+    /// change, clutch time (within 5 points in the last 5 minutes) or final
+    #[clap(long)]
+    bell: bool,
+
+    /// Show the scoreboard saved in this HTML file instead of fetching it
+    #[clap(long, conflicts_with = "fixtures-dir")]
+    from_file: Option<PathBuf>,
+
+    /// Replay the scoreboards saved in this directory, one file per refresh (in file name order)
+    #[clap(long)]
+    fixtures_dir: Option<PathBuf>,
+
+    /// Save every fetched scoreboard page into this directory (to replay with --fixtures-dir)
+    #[clap(long, conflicts_with_all = &["from-file", "fixtures-dir"])]
+    record: Option<PathBuf>,
 }
 
-// end Params
+/// Picks the provider to get the games from. Saved scoreboards take precedence over `--provider`.
+fn select_provider(args: &Args) -> Result<Arc<dyn ScoreProvider>, ProviderError> {
+    if let Some(path) = &args.from_file {
+        return Ok(Arc::new(FixtureProvider::from_file(path)?));
+    }
+    if let Some(dir) = &args.fixtures_dir {
+        return Ok(Arc::new(FixtureProvider::from_dir(dir)?));
+    }
+    let recorder = match &args.record {
+        Some(dir) => Some(Recorder::new(dir).map_err(|err| ProviderError::Record(dir.clone(), err))?),
+        None => None,
+    };
+    return Ok(create_provider(args.provider, recorder));
+}
 
-fn print_type_of<T>(_: &T) {
-    println!("{}", std::any::type_name::<T>())
+/// Picks the provider to get the games from, keeping only the games of the teams asked for with
+/// `--team`
+fn select_filtered_provider(args: &Args) -> Result<Arc<dyn ScoreProvider>, ProviderError> {
+    let provider = select_provider(args)?;
+    if args.team.is_empty() {
+        return Ok(provider);
+    }
+    return Ok(Arc::new(TeamFilter::new(provider, args.team.clone())));
+}
+
+/// Returns every date of the range asked for with --from/--to or --date <from>..<to>, if any
+fn requested_date_range(args: &Args) -> Option<Result<Vec<NaiveDate>, DateError>> {
+    if let Some(from) = &args.from {
+        return Some(extract_date_range(from, args.to.as_deref().unwrap_or("t")));
+    }
+    let (from, to) = args.date.split_once("..")?;
+    return Some(extract_date_range(from, to));
+}
+
+/// Ends the program with the error if an argument or the config file could not be understood, the
+/// saved scoreboards they point to could not be read, or a scoreboard shown only once could not be
+/// retrieved
+fn exit_on_error<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    }
 }
 
+// end Params
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-     better_panic::install();
+    better_panic::install();
     setup_panic_hook();
-    //stdin controls user input
-    let mut stdin = async_stdin().bytes();
     // Parse command line arguments
     let args = Args::parse();
-    // handle date
-    let date = extract_date_argument(&args.date);
-    // form the url to look for based on the date.
-    let url_base = String::from("https://scores.nbcsports.com/nba/scoreboard.asp?day=");
-    let url = url_base + &date;
-
-    // program loop -- re-fetch html and display games every 10 seconds
-    'program_loop: loop {
-        // controller for detecting 'q' key to exit program
-        // Get the webpage
-        let resp = reqwest::get(&url).await?;
-        assert!(resp.status().is_success());
-        let document = Document::from(&*resp.text().await?);
-
-        // clear terminal and set program to write in top left of terminal
-        clear_terminal();
-        print_header();
-
-        for row in document.find(Class("shsScoreboardRow")) {
-            // there are two games per row
-            for game_block in row.find(Class("shsScoreboardCol")) {
-                // given a game block, form two Teams and a Game
-                // parse the current game block for game info
-                let game: Game = form_game(game_block);
-                //println!("{:?}", game.home_team);
-                //println!("{:?}", game.away_team);
-
-                // print current game info to terminal
-                game.display();
-            }
-        }
+    // the live scoreboard needs a terminal, anything else gets the scoreboard once
+    let is_tty = termion::is_tty(&stdout());
+    let color = if args.plain { ColorChoice::Never } else { args.color };
+    colored::control::set_override(use_color(color, is_tty));
+    set_color_depth(args.color_depth);
+    set_time_zone(args.tz);
+    // where the games are retrieved from
+    let provider = exit_on_error(select_filtered_provider(&args));
+    // favorite teams and keys are set in the config file
+    let config = exit_on_error(Config::load());
+    let display_options = DisplayOptions {
+        linescore: args.linescore,
+        leaders: args.leaders,
+        name_style: args.names,
+        favorites: exit_on_error(config.favorite_teams()),
+        sort: args.sort,
+    };
 
-        // loop to get user input -- lasts 10 seconds and then re-runs program loop
-        let mut counter = 0;
-        'inner: loop {
-            //let b = bytes.next().unwrap().unwrap();
-            let stdout = stdout();
-            // raw mode means no stdin will print to the terminal
-            let mut stdout = stdout.lock().into_raw_mode().unwrap();
-            let b = stdin.next();
-            // this is the async read input char, it looks for user input
-            write!(stdout, "\r").unwrap();
-            // cases for buttons to press
-            // TODO: Refactor into key handling module
-            if let Some(Ok(b'q')) = b {
-                // clean up and end program
-                cleanup_terminal();
-                break 'program_loop;
-            }
-            // debug key
-            if let Some(Ok(b'd')) = b {
-                println!("Debug button Pressed!");
+    // a range of days is printed once, there is nothing live to keep refreshing
+    if let Some(dates) = requested_date_range(&args) {
+        let dates = exit_on_error(dates);
+        let mut days = Vec::new();
+        for (date, games) in fetch_scoreboards(provider, dates).await {
+            match games {
+                Ok(games) => days.push((date, games)),
+                // the other days are still worth showing, the failed ones are left out
+                Err(err) => eprintln!("{}: {}", date, err),
             }
+        }
+        match args.format {
+            OutputFormat::Table => print_date_range(&mut stdout(), &days, &display_options)?,
+            format => write_scoreboards(&mut stdout(), format, &days)?,
+        }
+        return Ok(());
+    }
 
-            let sleep_time_in_ms = 50;
-            let sleep_time = time::Duration::from_millis(sleep_time_in_ms);
-            thread::sleep(sleep_time);
-            counter += 1;
-            // wait 10 seconds before re-running program
-            if counter * sleep_time_in_ms >= 10000 {
-                break 'inner;
-            }
+    // handle date -- it can be changed from the keyboard while the program runs
+    let date = exit_on_error(extract_date_argument(&args.date));
+    match args.format {
+        OutputFormat::Table if args.plain || !is_tty => {
+            let games = exit_on_error(provider.fetch_scoreboard(date).await);
+            print_day(&mut stdout(), date, &games, &display_options)?;
+            return Ok(());
+        }
+        OutputFormat::Table => {}
+        OutputFormat::Json | OutputFormat::Csv | OutputFormat::Tsv => {
+            let games = exit_on_error(provider.fetch_scoreboard(date).await);
+            write_scoreboard(&mut stdout(), args.format, date, &games)?;
+            return Ok(());
+        }
+        OutputFormat::Ndjson => {
+            watch_ndjson(&mut stdout(), provider, date, args.interval, &config.hooks).await?;
+            return Ok(());
         }
     }
+    let keys = exit_on_error(KeyBindings::new(&config.keys));
+    App::new(provider, date, display_options, args.interval, keys, args.bell, config.hooks).run().await?;
     return Ok(());
 }
 
@@ -0,0 +1,306 @@
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+use tokio::task::JoinHandle;
+
+use crate::events::{diff_games, Leaders};
+use crate::game::Game;
+use crate::hooks::Hooks;
+use crate::html_parser::ParseError;
+use crate::team::Team;
+use crate::provider::ScoreProvider;
+use crate::refresh::{all_over, RefreshPolicy, RETRY_INTERVAL};
+
+/// How the scoreboard is written out
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The colored scoreboard, live in the terminal
+    Table,
+    /// A single JSON document, written once
+    Json,
+    /// One JSON object per line, written after every refresh
+    Ndjson,
+    /// Comma separated values, one row per game
+    Csv,
+    /// Tab separated values, one row per game
+    Tsv,
+}
+
+/// The scoreboard of a day as written to JSON
+#[derive(Serialize)]
+struct ScoreboardJson<'a> {
+    date: NaiveDate,
+    fetched_at: DateTime<Local>,
+    games: Vec<GameJson<'a>>,
+}
+
+/// A game as written to JSON: the game itself, or why it could not be read
+#[derive(Serialize)]
+#[serde(untagged)]
+enum GameJson<'a> {
+    Game(&'a Game),
+    Unreadable { error: String },
+}
+
+impl<'a> ScoreboardJson<'a> {
+    fn new(date: NaiveDate, games: &'a [Result<Game, ParseError>]) -> ScoreboardJson<'a> {
+        let games = games
+            .iter()
+            .map(|game| match game {
+                Ok(game) => GameJson::Game(game),
+                Err(err) => GameJson::Unreadable { error: err.to_string() },
+            })
+            .collect();
+        ScoreboardJson { date, fetched_at: Local::now(), games }
+    }
+}
+
+/// Writes the scoreboard of a day in a machine readable format, no colors or terminal codes
+/// included: a JSON object, a single line of it for NDJSON, or a header and one row per game for
+/// CSV and TSV.
+///
+/// # Examples
+///
+/// ```
+/// let games = provider.fetch_scoreboard(date).await?;
+/// write_scoreboard(&mut stdout(), OutputFormat::Json, date, &games)?;
+/// ```
+pub fn write_scoreboard(out: &mut dyn Write, format: OutputFormat, date: NaiveDate,
+                        games: &[Result<Game, ParseError>]) -> io::Result<()> {
+    let scoreboard = ScoreboardJson::new(date, games);
+    match format {
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut *out, &scoreboard)?,
+        OutputFormat::Ndjson => serde_json::to_writer(&mut *out, &scoreboard)?,
+        OutputFormat::Csv | OutputFormat::Tsv => return write_delimited(out, format, &[(date, games)]),
+        OutputFormat::Table => unreachable!("the table is printed by the display module"),
+    }
+    writeln!(out)?;
+    return out.flush();
+}
+
+/// Writes the scoreboards of a range of days in a machine readable format: a JSON list of
+/// scoreboards, one line per day for NDJSON, or a single header followed by the rows of every game
+/// of every day for CSV and TSV.
+///
+/// # Examples
+///
+/// ```
+/// let week = fetch_scoreboards(provider, dates).await?;
+/// write_scoreboards(&mut stdout(), OutputFormat::Ndjson, &week)?;
+/// ```
+pub fn write_scoreboards(out: &mut dyn Write, format: OutputFormat,
+                         days: &[(NaiveDate, Vec<Result<Game, ParseError>>)]) -> io::Result<()> {
+    if matches!(format, OutputFormat::Csv | OutputFormat::Tsv) {
+        let days: Vec<(NaiveDate, &[Result<Game, ParseError>])> = days
+            .iter()
+            .map(|(date, games)| (*date, games.as_slice()))
+            .collect();
+        return write_delimited(out, format, &days);
+    }
+    if format != OutputFormat::Json {
+        for (date, games) in days {
+            write_scoreboard(out, format, *date, games)?;
+        }
+        return Ok(());
+    }
+    let scoreboards: Vec<ScoreboardJson> = days
+        .iter()
+        .map(|(date, games)| ScoreboardJson::new(*date, games))
+        .collect();
+    serde_json::to_writer_pretty(&mut *out, &scoreboards)?;
+    writeln!(out)?;
+    return out.flush();
+}
+
+/// Writes the games of the given days as CSV or TSV, one row per game under a header row:
+///
+/// ```text
+/// date,away,home,away_score,home_score,status,away_1,...,away_ot,home_1,...,home_ot,away_pts_leader,away_pts,...
+/// 2026-10-10,Boston,LA Lakers,106,104,Final/2OT,30,25,12,20,19,...
+/// ```
+///
+/// The 4 quarters each have a column, overtimes are added up in the `ot` column. Scores, periods
+/// and leaders a game doesn't have (yet) are left empty. Games that could not be read are left out
+/// and reported on stderr.
+fn write_delimited(out: &mut dyn Write, format: OutputFormat,
+                   days: &[(NaiveDate, &[Result<Game, ParseError>])]) -> io::Result<()> {
+    let delimiter = if format == OutputFormat::Tsv { b'\t' } else { b',' };
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(&mut *out);
+
+    let mut header: Vec<String> = ["date", "away", "home", "away_score", "home_score", "status"]
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+    for side in ["away", "home"] {
+        header.extend((1..=4).map(|quarter| format!("{}_{}", side, quarter)));
+        header.push(format!("{}_ot", side));
+    }
+    for side in ["away", "home"] {
+        for (category, _, _) in Team::default().leaders() {
+            let category = category.to_lowercase();
+            header.push(format!("{}_{}_leader", side, category));
+            header.push(format!("{}_{}", side, category));
+        }
+    }
+    writer.write_record(&header)?;
+
+    for (date, games) in days {
+        for game in games.iter() {
+            let game = match game {
+                Ok(game) => game,
+                Err(err) => {
+                    eprintln!("{}: left out unreadable game: {}", date, err);
+                    continue;
+                }
+            };
+            // games that haven't started have no score yet
+            let score = |team: &Team| if game.status.has_started() { team.score.to_string() } else { String::new() };
+            let mut row: Vec<String> = vec![
+                date.to_string(),
+                game.away_team.name.clone(),
+                game.home_team.name.clone(),
+                score(&game.away_team),
+                score(&game.home_team),
+                game.status.to_string(),
+            ];
+            for team in [&game.away_team, &game.home_team] {
+                row.extend((0..4).map(|quarter| match team.period_scores.get(quarter) {
+                    Some(score) => score.to_string(),
+                    None => String::new(),
+                }));
+                let overtimes = team.period_scores.get(4..).unwrap_or_default();
+                match overtimes {
+                    [] => row.push(String::new()),
+                    _ => row.push(overtimes.iter().sum::<u32>().to_string()),
+                }
+            }
+            for team in [&game.away_team, &game.home_team] {
+                for (_, name, value) in team.leaders() {
+                    // games that haven't started have no leaders
+                    if name.is_empty() {
+                        row.extend([String::new(), String::new()]);
+                    } else {
+                        row.extend([name.to_string(), value.to_string()]);
+                    }
+                }
+            }
+            writer.write_record(&row)?;
+        }
+    }
+    writer.flush()?;
+    return Ok(());
+}
+
+/// Keeps writing the scoreboard of `date` as NDJSON, one line per refresh, until every game is over
+/// whatever the refresh policy. The hooks of what happened in the games are run after each
+/// refresh. A failed fetch or hook is reported on stderr, a failed fetch is tried again.
+///
+/// # Arguments
+///
+/// * `out` - Where the lines are written
+/// * `provider` - Where the games are retrieved from
+/// * `date` - The day of the scoreboard
+/// * `policy` - Decides when the scoreboard is fetched again
+/// * `hooks` - Commands run when something happens in a game
+pub async fn watch_ndjson(out: &mut dyn Write, provider: Arc<dyn ScoreProvider>, date: NaiveDate,
+                          policy: RefreshPolicy, hooks: &Hooks) -> io::Result<()> {
+    let (failures, mut failed) = tokio::sync::mpsc::unbounded_channel::<String>();
+    tokio::spawn(async move {
+        while let Some(failure) = failed.recv().await {
+            eprintln!("{}", failure);
+        }
+    });
+    let mut previous: Option<Vec<Result<Game, ParseError>>> = None;
+    let mut leaders = Leaders::new();
+    // hooks still running, waited for before returning so the last ones (on_final) are not cut off
+    let mut hook_tasks: Vec<JoinHandle<()>> = Vec::new();
+    loop {
+        let delay = match provider.fetch_scoreboard(date).await {
+            Ok(games) => {
+                write_scoreboard(out, OutputFormat::Ndjson, date, &games)?;
+                if let Some(previous) = &previous {
+                    hook_tasks.retain(|task| !task.is_finished());
+                    hook_tasks.extend(hooks.run(date, &diff_games(previous, &games, &mut leaders), &failures));
+                }
+                // a fixed interval would keep writing the same finals forever
+                let delay = if all_over(date, &games) { None } else { policy.next_refresh(date, &games) };
+                previous = Some(games);
+                match delay {
+                    Some(delay) => delay,
+                    // nothing left to refresh
+                    None => {
+                        for task in hook_tasks {
+                            let _ = task.await;
+                        }
+                        return Ok(());
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                RETRY_INTERVAL
+            }
+        };
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{create_nonstarted_game, GameInfo};
+    use crate::game_status::GameStatus;
+
+    /// Boston @ LA Lakers with the given points per period, and leaders for Boston only
+    fn game(status: GameStatus, away_periods: &[u32], home_periods: &[u32]) -> Result<Game, ParseError> {
+        let mut game = create_nonstarted_game("LA Lakers", "Boston", status, GameInfo::default());
+        for (team, periods) in [(&mut game.away_team, away_periods), (&mut game.home_team, home_periods)] {
+            team.period_scores = periods.to_vec();
+            team.score = periods.iter().sum();
+        }
+        game.away_team.points_leader = String::from("Jayson Tatum");
+        game.away_team.points_leader_value = 30;
+        return Ok(game);
+    }
+
+    /// The rows written for the games of a day, header left out
+    fn rows(format: OutputFormat, games: &[Result<Game, ParseError>]) -> Vec<String> {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 10).unwrap();
+        let mut out = Vec::new();
+        write_delimited(&mut out, format, &[(date, games)]).unwrap();
+        return String::from_utf8(out).unwrap().lines().skip(1).map(String::from).collect();
+    }
+
+    #[test]
+    fn writes_regulation_games() {
+        let games = [game(GameStatus::Final, &[30, 25, 28, 27], &[20, 28, 15, 30])];
+        assert_eq!(rows(OutputFormat::Csv, &games), vec![
+            "2026-10-10,Boston,LA Lakers,110,93,Final,30,25,28,27,,20,28,15,30,,Jayson Tatum,30,,,,,,,,,,",
+        ]);
+    }
+
+    #[test]
+    fn adds_up_overtimes() {
+        let games = [game(GameStatus::FinalOT(2), &[30, 25, 20, 25, 10, 12], &[25, 30, 25, 20, 10, 9])];
+        assert_eq!(rows(OutputFormat::Csv, &games), vec![
+            "2026-10-10,Boston,LA Lakers,122,119,Final/2OT,30,25,20,25,22,25,30,25,20,19,Jayson Tatum,30,,,,,,,,,,",
+        ]);
+    }
+
+    #[test]
+    fn leaves_out_what_games_do_not_have() {
+        let scheduled = GameStatus::Scheduled { start: String::from("7:30 PM ET") };
+        let games = [
+            Ok(create_nonstarted_game("Chicago", "Miami", scheduled, GameInfo::default())),
+            Err(ParseError::MissingTeamNames),
+            game(GameStatus::InProgress { period: 2, clock: String::from("5:32") }, &[30, 12], &[20, 15]),
+        ];
+        assert_eq!(rows(OutputFormat::Tsv, &games), vec![
+            "2026-10-10\tMiami\tChicago\t\t\t7:30 PM ET\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t\t",
+            "2026-10-10\tBoston\tLA Lakers\t42\t35\t2nd 5:32\t30\t12\t\t\t\t20\t15\t\t\t\tJayson Tatum\t30\t\t\t\t\t\t\t\t\t\t",
+        ]);
+    }
+}
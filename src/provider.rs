@@ -0,0 +1,206 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use select::document::Document;
+use tokio::sync::Semaphore;
+
+use crate::game::Game;
+use crate::html_parser::{parse_scoreboard, ParseError};
+use crate::recorder::Recorder;
+use crate::teams::TeamInfo;
+
+/// Most scoreboards fetched at the same time by `fetch_scoreboards`, so a long range of days
+/// doesn't hit the site with a request per day all at once
+const MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// Errors that can happen while a provider is retrieving a scoreboard.
+#[derive(Debug)]
+pub enum ProviderError {
+    /// The request could not be sent or the response body could not be read
+    Request(reqwest::Error),
+    /// The site answered, but not with a success status code
+    Status(reqwest::StatusCode),
+    /// A saved scoreboard could not be read from disk
+    Io(io::Error),
+    /// A fetched scoreboard could not be saved into the given directory
+    Record(PathBuf, io::Error),
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProviderError::Request(err) => write!(f, "could not fetch scoreboard: {}", err),
+            ProviderError::Status(status) => write!(f, "scoreboard request failed with status {}", status),
+            ProviderError::Io(err) => write!(f, "could not read saved scoreboard: {}", err),
+            ProviderError::Record(dir, err) => write!(f, "could not save scoreboard into {}: {}", dir.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for ProviderError {}
+
+impl From<reqwest::Error> for ProviderError {
+    fn from(err: reqwest::Error) -> ProviderError {
+        ProviderError::Request(err)
+    }
+}
+
+impl From<io::Error> for ProviderError {
+    fn from(err: io::Error) -> ProviderError {
+        ProviderError::Io(err)
+    }
+}
+
+/// A source of NBA scoreboards. Everything past fetching (displaying, refreshing, ...) only talks
+/// to this trait, so a new site can be supported by adding another implementation.
+#[async_trait]
+pub trait ScoreProvider: Send + Sync {
+    /// Retrieves every game played (or scheduled) on the given date. A game the provider could not
+    /// make sense of is returned as a `ParseError` in its place.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date of the games to retrieve
+    async fn fetch_scoreboard(&self, date: NaiveDate) -> Result<Vec<Result<Game, ParseError>>, ProviderError>;
+}
+
+/// The available score providers, selectable from the command line
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ProviderKind {
+    /// scores.nbcsports.com
+    NbcSports,
+}
+
+/// Fetches the scoreboards of several days at once, each day in its own tokio task with at most 4
+/// of them fetching at a time. The scoreboards are returned in the order of `dates`, each with
+/// its own result: a day that could not be fetched doesn't stop the others.
+///
+/// # Examples
+///
+/// ```
+/// let week = fetch_scoreboards(provider.clone(), vec![monday, tuesday]).await;
+/// for (date, games) in week {
+///     match games {
+///         Ok(games) => println!("{}: {} games", date, games.len()),
+///         Err(err) => eprintln!("{}: {}", date, err),
+///     }
+/// }
+/// ```
+pub async fn fetch_scoreboards(provider: Arc<dyn ScoreProvider>, dates: Vec<NaiveDate>)
+    -> Vec<(NaiveDate, Result<Vec<Result<Game, ParseError>>, ProviderError>)> {
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let handles: Vec<_> = dates
+        .into_iter()
+        .map(|date| {
+            let provider = provider.clone();
+            let permits = permits.clone();
+            tokio::spawn(async move {
+                // the semaphore is never closed
+                let _permit = permits.acquire_owned().await.expect("fetch semaphore closed");
+                (date, provider.fetch_scoreboard(date).await)
+            })
+        })
+        .collect();
+
+    let mut scoreboards = Vec::new();
+    for handle in handles {
+        // a panicking fetch is a bug, not a provider error -- pass it on
+        scoreboards.push(handle.await.expect("scoreboard fetch panicked"));
+    }
+    return scoreboards;
+}
+
+/// Creates the provider behind the given `ProviderKind`
+///
+/// # Arguments
+///
+/// * `kind` - Which provider to create
+/// * `recorder` - If given, every page the provider fetches is archived with it
+pub fn create_provider(kind: ProviderKind, recorder: Option<Recorder>) -> Arc<dyn ScoreProvider> {
+    match kind {
+        ProviderKind::NbcSports => {
+            let mut provider = NbcSportsProvider::new();
+            provider.recorder = recorder;
+            Arc::new(provider)
+        }
+    }
+}
+
+/// Keeps only the games of some teams from the scoreboards of another provider. Games that could
+/// not be read are kept, they could be between any teams.
+pub struct TeamFilter {
+    provider: Arc<dyn ScoreProvider>,
+    teams: Vec<&'static TeamInfo>,
+}
+
+impl TeamFilter {
+    pub fn new(provider: Arc<dyn ScoreProvider>, teams: Vec<&'static TeamInfo>) -> TeamFilter {
+        TeamFilter { provider, teams }
+    }
+}
+
+#[async_trait]
+impl ScoreProvider for TeamFilter {
+    async fn fetch_scoreboard(&self, date: NaiveDate) -> Result<Vec<Result<Game, ParseError>>, ProviderError> {
+        let mut games = self.provider.fetch_scoreboard(date).await?;
+        games.retain(|game| match game {
+            Ok(game) => game.involves(&self.teams),
+            Err(_) => true,
+        });
+        return Ok(games);
+    }
+}
+
+/// Scrapes the scoreboard page of NBC Sports
+pub struct NbcSportsProvider {
+    url_base: String,
+    recorder: Option<Recorder>,
+}
+
+impl NbcSportsProvider {
+    pub fn new() -> NbcSportsProvider {
+        NbcSportsProvider {
+            url_base: String::from("https://scores.nbcsports.com/nba/scoreboard.asp?day="),
+            recorder: None,
+        }
+    }
+
+    /// Forms the url of the scoreboard page for the given date
+    fn url(&self, date: NaiveDate) -> String {
+        format!("{}{}", self.url_base, date.format("%Y%m%d"))
+    }
+}
+
+impl Default for NbcSportsProvider {
+    fn default() -> NbcSportsProvider {
+        NbcSportsProvider::new()
+    }
+}
+
+#[async_trait]
+impl ScoreProvider for NbcSportsProvider {
+    async fn fetch_scoreboard(&self, date: NaiveDate) -> Result<Vec<Result<Game, ParseError>>, ProviderError> {
+        // Get the webpage
+        let url = self.url(date);
+        let resp = reqwest::get(&url).await?;
+        let status = resp.status();
+        let html = resp.text().await?;
+        // archive the page before anything else, failed requests are worth keeping too
+        if let Some(recorder) = &self.recorder {
+            recorder
+                .record(date, &url, status, &html)
+                .await
+                .map_err(|err| ProviderError::Record(recorder.dir().to_path_buf(), err))?;
+        }
+        if !status.is_success() {
+            return Err(ProviderError::Status(status));
+        }
+        let document = Document::from(&*html);
+        return Ok(parse_scoreboard(&document, date));
+    }
+}
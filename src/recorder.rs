@@ -0,0 +1,54 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDate};
+
+/// Archives every fetched scoreboard page to a directory, so real nights of games can be replayed
+/// later (see `FixtureProvider`) or inspected after the site's layout changes.
+pub struct Recorder {
+    dir: PathBuf,
+}
+
+impl Recorder {
+    /// Creates a recorder writing into `dir`, creating the directory if needed
+    pub fn new(dir: &Path) -> io::Result<Recorder> {
+        std::fs::create_dir_all(dir)?;
+        return Ok(Recorder {
+            dir: dir.to_path_buf(),
+        });
+    }
+
+    /// The directory pages are saved into
+    pub fn dir(&self) -> &Path {
+        return &self.dir;
+    }
+
+    /// Writes the raw HTML of a fetched page to a new file and returns its path. The fetch details
+    /// are kept in a comment at the top so the file is still a valid page to replay.
+    ///
+    /// Files are named `<fetch timestamp>_<date>.html`, so sorting them by name gives the order
+    /// they were fetched in, even when the day shown was changed while recording.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date the scoreboard was requested for
+    /// * `url` - The url the page was fetched from
+    /// * `status` - The HTTP status of the response
+    /// * `html` - The body of the response
+    pub async fn record(&self, date: NaiveDate, url: &str, status: reqwest::StatusCode, html: &str) -> io::Result<PathBuf> {
+        let fetched_at = Local::now();
+        let path = self
+            .dir
+            .join(format!("{}_{}.html", fetched_at.format("%Y%m%dT%H%M%S%3f"), date.format("%Y%m%d")));
+        let contents = format!(
+            "<!--\ndate: {}\nurl: {}\nfetched: {}\nstatus: {}\n-->\n{}",
+            date,
+            url,
+            fetched_at.to_rfc3339(),
+            status,
+            html
+        );
+        tokio::fs::write(&path, contents).await?;
+        return Ok(path);
+    }
+}
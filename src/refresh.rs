@@ -0,0 +1,160 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::date_handler::today;
+use crate::game::Game;
+use crate::game_status::GameStatus;
+use crate::html_parser::ParseError;
+
+/// How often to refresh while a game is being played (adaptive mode)
+const LIVE_INTERVAL: Duration = Duration::from_secs(5);
+/// How often to refresh while today's games have yet to start (adaptive mode)
+const PREGAME_INTERVAL: Duration = Duration::from_secs(60);
+/// How often to refresh the games of a day still to come (adaptive mode)
+const FUTURE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// How often to refresh when the state of the games is unclear (adaptive mode)
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How long to wait before trying again after a failed fetch
+pub const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Decides when the live scoreboard is fetched again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshPolicy {
+    /// Always refresh after the same amount of time
+    Every(Duration),
+    /// Refresh quickly while games are being played, slowly while they are far from starting, and
+    /// not at all once every game is over
+    Adaptive,
+}
+
+impl FromStr for RefreshPolicy {
+    type Err = String;
+
+    /// Parses "adaptive" or a number of seconds
+    fn from_str(interval: &str) -> Result<RefreshPolicy, String> {
+        if interval.eq_ignore_ascii_case("adaptive") {
+            return Ok(RefreshPolicy::Adaptive);
+        }
+        match interval.parse::<u64>() {
+            Ok(seconds) if seconds > 0 => Ok(RefreshPolicy::Every(Duration::from_secs(seconds))),
+            _ => Err(format!("'{}' is not a number of seconds (> 0) or \"adaptive\"", interval)),
+        }
+    }
+}
+
+impl RefreshPolicy {
+    /// Returns how long to wait before fetching the scoreboard of `date` again, given the games it
+    /// just returned. None means there is nothing left to refresh.
+    pub fn next_refresh(&self, date: NaiveDate, games: &[Result<Game, ParseError>]) -> Option<Duration> {
+        match self {
+            RefreshPolicy::Every(interval) => Some(*interval),
+            RefreshPolicy::Adaptive => adaptive_interval(date, games),
+        }
+    }
+}
+
+/// True once every game of the day is over: final, postponed or cancelled. Never true for a day
+/// still to come, games can be added to it, nor while a game could not be read.
+///
+/// # Arguments
+///
+/// * `date` - The day of the games
+/// * `games` - The games of that day, as last fetched
+pub fn all_over(date: NaiveDate, games: &[Result<Game, ParseError>]) -> bool {
+    if date > today() {
+        return false;
+    }
+    return games.iter().all(|game| match game {
+        Ok(game) => game.status.is_final() || matches!(game.status, GameStatus::Postponed | GameStatus::Cancelled),
+        // games that could not be read could be in any state
+        Err(_) => false,
+    });
+}
+
+/// The refresh interval of the adaptive policy, based on where the games of the day stand
+fn adaptive_interval(date: NaiveDate, games: &[Result<Game, ParseError>]) -> Option<Duration> {
+    if all_over(date, games) {
+        return None;
+    }
+    if games.iter().any(|game| game.is_err()) {
+        return Some(DEFAULT_INTERVAL);
+    }
+    if games.iter().flatten().any(|game| game.status.is_live()) {
+        return Some(LIVE_INTERVAL);
+    }
+    // everything left is scheduled, or over on a day to come
+    if date > today() {
+        return Some(FUTURE_INTERVAL);
+    }
+    return Some(PREGAME_INTERVAL);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{create_nonstarted_game, GameInfo};
+
+    fn game(status: GameStatus) -> Result<Game, ParseError> {
+        return Ok(create_nonstarted_game("LA Lakers", "Boston", status, GameInfo::default()));
+    }
+
+    fn scheduled() -> Result<Game, ParseError> {
+        return game(GameStatus::Scheduled { start: String::from("7:30 PM ET") });
+    }
+
+    fn in_progress() -> Result<Game, ParseError> {
+        return game(GameStatus::InProgress { period: 3, clock: String::from("5:32") });
+    }
+
+    #[test]
+    fn parses_intervals() {
+        assert_eq!("adaptive".parse::<RefreshPolicy>().unwrap(), RefreshPolicy::Adaptive);
+        assert_eq!("Adaptive".parse::<RefreshPolicy>().unwrap(), RefreshPolicy::Adaptive);
+        assert_eq!("30".parse::<RefreshPolicy>().unwrap(), RefreshPolicy::Every(Duration::from_secs(30)));
+        assert!("0".parse::<RefreshPolicy>().is_err());
+        assert!("-5".parse::<RefreshPolicy>().is_err());
+        assert!("fast".parse::<RefreshPolicy>().is_err());
+    }
+
+    #[test]
+    fn refreshes_quickly_while_games_are_played() {
+        let games = [game(GameStatus::Final), in_progress(), scheduled()];
+        assert_eq!(adaptive_interval(today(), &games), Some(LIVE_INTERVAL));
+        // a delay can end any time
+        let games = [game(GameStatus::Delayed { started: true }), scheduled()];
+        assert_eq!(adaptive_interval(today(), &games), Some(LIVE_INTERVAL));
+    }
+
+    #[test]
+    fn refreshes_slowly_before_games_start() {
+        let games = [game(GameStatus::Final), scheduled()];
+        assert_eq!(adaptive_interval(today(), &games), Some(PREGAME_INTERVAL));
+        let tomorrow = today().succ_opt().unwrap();
+        assert_eq!(adaptive_interval(tomorrow, &[scheduled()]), Some(FUTURE_INTERVAL));
+        // games can still be added to a day to come
+        assert_eq!(adaptive_interval(tomorrow, &[]), Some(FUTURE_INTERVAL));
+    }
+
+    #[test]
+    fn stops_once_every_game_is_over() {
+        let games = [game(GameStatus::Final), game(GameStatus::FinalOT(2)), game(GameStatus::Postponed),
+                     game(GameStatus::Cancelled)];
+        assert!(all_over(today(), &games));
+        assert_eq!(adaptive_interval(today(), &games), None);
+        let yesterday = today().pred_opt().unwrap();
+        assert_eq!(adaptive_interval(yesterday, &[]), None);
+        // a fixed interval keeps going, `watch_ndjson` stops on `all_over` itself
+        assert_eq!(RefreshPolicy::Every(Duration::from_secs(10)).next_refresh(today(), &games),
+                   Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn keeps_refreshing_unreadable_games() {
+        let games = [game(GameStatus::Final), Err(ParseError::MissingTeamNames)];
+        assert!(!all_over(today(), &games));
+        assert_eq!(adaptive_interval(today(), &games), Some(DEFAULT_INTERVAL));
+    }
+}
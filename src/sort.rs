@@ -0,0 +1,90 @@
+use clap::ValueEnum;
+
+use crate::game::Game;
+use crate::game_status::GameStatus;
+use crate::html_parser::ParseError;
+
+/// The order games are listed in on the scoreboard. Games that compare equal keep the order of the
+/// page.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Games that have started first, then the others in the order they tip off
+    Start,
+    /// Games being played first, then the ones yet to start, finished games last
+    Status,
+    /// Like status, the closest games first within each group
+    Closeness,
+    /// Games of favorite teams first, the others as the page lists them
+    #[default]
+    Favorites,
+}
+
+impl SortOrder {
+    /// Every sort order, in the order the live scoreboard cycles through them
+    const ALL: [SortOrder; 4] = [SortOrder::Favorites, SortOrder::Start, SortOrder::Status, SortOrder::Closeness];
+
+    /// The sort order after this one when cycling through them
+    pub fn next(&self) -> SortOrder {
+        let index = SortOrder::ALL.iter().position(|order| order == self).unwrap();
+        return SortOrder::ALL[(index + 1) % SortOrder::ALL.len()];
+    }
+
+    /// What the games are sorted by, as shown under the live scoreboard
+    pub fn description(&self) -> &'static str {
+        match self {
+            SortOrder::Start => "start time",
+            SortOrder::Status => "status",
+            SortOrder::Closeness => "closeness",
+            SortOrder::Favorites => "favorite teams",
+        }
+    }
+
+    /// The key a game is sorted by in this order, lowest first
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game, or why it could not be read
+    /// * `favorite` - Whether a favorite team plays in the game
+    pub fn key(&self, game: &Result<Game, ParseError>, favorite: bool) -> (u8, i64) {
+        match self {
+            SortOrder::Start => start_key(game),
+            SortOrder::Status => (status_rank(game), 0),
+            SortOrder::Closeness => (status_rank(game), i64::from(margin(game))),
+            SortOrder::Favorites => (!favorite as u8, 0),
+        }
+    }
+}
+
+/// Where a game goes when sorting by start time: games that have started (the page doesn't give
+/// their start time any more), then games by tip-off time, then games without one ("TBD") or that
+/// could not be read
+fn start_key(game: &Result<Game, ParseError>) -> (u8, i64) {
+    let Ok(game) = game else { return (2, 0) };
+    if game.status.has_started() {
+        return (0, 0);
+    }
+    return match game.info.start {
+        Some(start) => (1, start.timestamp()),
+        None => (2, 0),
+    };
+}
+
+/// Where a game goes when sorting by status: being played, yet to start, not played at all (or
+/// unreadable), over
+fn status_rank(game: &Result<Game, ParseError>) -> u8 {
+    let Ok(game) = game else { return 2 };
+    match &game.status {
+        status if status.is_live() => 0,
+        GameStatus::Scheduled { .. } => 1,
+        status if status.is_final() => 3,
+        _ => 2,
+    }
+}
+
+/// The difference between the scores of the two teams, 0 for a game that could not be read
+fn margin(game: &Result<Game, ParseError>) -> u32 {
+    match game {
+        Ok(game) => game.home_team.score.abs_diff(game.away_team.score),
+        Err(_) => 0,
+    }
+}
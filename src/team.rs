@@ -1,12 +1,20 @@
-use crate::constants::TEAM_COLORS;
+use serde::{Serialize, Serializer};
 
-#[derive(Debug)]
+use crate::teams::{find_team, Conference, Division, NameStyle, TeamInfo, UNKNOWN_TEAM_COLORS};
+
+// Default gives a nameless team with no score or leaders
+#[derive(Debug, Default, Serialize)]
 pub struct Team {
-    // team name
+    // team name, as scraped off the scoreboard
     pub name: String,
+    // the team in the registry, None for a team it doesn't know
+    #[serde(rename = "team", serialize_with = "serialize_team_info")]
+    pub info: Option<&'static TeamInfo>,
     // team score -- TODO Does it make sense to make this mutable? Yes if the program runs in a
     // loop later on
     pub score: u32,
+    // points scored in each period played so far -- 4 quarters followed by any overtimes
+    pub period_scores: Vec<u32>,
     // points leader name and their number of points scored
     pub points_leader: String,
     pub points_leader_value: u32,
@@ -18,59 +26,92 @@ pub struct Team {
     pub assists_leader_value: u32,
 
     // Color information
+    #[serde(rename = "color")]
     pub color_value: (u8, u8, u8),
+    #[serde(rename = "secondary_color")]
+    pub secondary_color_value: (u8, u8, u8),
 }
 
-// default constructor when passed no arguments to
-impl Default for Team {
-    fn default() -> Team {
-        Team {
-            // team name
-            name: String::from(""),
-            // team score
-            score: 0,
-            // points leader name and their number of points scored
-            points_leader: String::from(""),
-            points_leader_value: 0,
-            // rebounds leader name and number of rebounds
-            rebounds_leader: String::from(""),
-            rebounds_leader_value: 0,
-            // assists leader name and number of assists
-            assists_leader: String::from(""),
-            assists_leader_value: 0,
-            color_value: (0, 0, 0)
-        }
-
+impl Team {
+    /// Creates a team with no score or leaders (yet), looking its name up in the registry. A team
+    /// the registry doesn't know keeps its name and gets neutral colors.
+    pub fn new(name: String) -> Team {
+        let info = find_team(&name);
+        let (color_value, secondary_color_value) = match info {
+            Some(info) => (info.primary_color, info.secondary_color),
+            None => UNKNOWN_TEAM_COLORS,
+        };
+        return Team {
+            name,
+            info,
+            color_value,
+            secondary_color_value,
+            ..Team::default()
+        };
     }
-}
 
-impl Team {
-    pub fn from_leader_vector(name: String, score: u32,
+    /// Creates a team that has started playing. Its leaders are given in the order points,
+    /// rebounds, assists; the page lists none right after tip-off, so leaders that are missing are
+    /// left empty.
+    pub fn from_leader_vector(name: String, score: u32, period_scores: Vec<u32>,
                           leader_names: Vec<String>, leader_values: Vec<u32>) -> Team {
+        let mut leaders = leader_names.into_iter().zip(leader_values);
+        let mut next_leader = || leaders.next().unwrap_or_default();
+        let (points_leader, points_leader_value) = next_leader();
+        let (rebounds_leader, rebounds_leader_value) = next_leader();
+        let (assists_leader, assists_leader_value) = next_leader();
         // create team from vectors of team leaders and values
-        Team {
-            name: String::from(&name),
+        return Team {
             score,
-            points_leader: String::from(leader_names
-                                        .get(0)
-                                        .expect("Could not read game leader")),
-            points_leader_value: *leader_values
-                .get(0)
-                .expect("Could not read game leader"),
-            rebounds_leader: String::from(leader_names
-                                          .get(1)
-                                          .expect("Could not read game leader")),
-            rebounds_leader_value: *leader_values
-                .get(1)
-                .expect("Could not read game leader"),
-            assists_leader: String::from(leader_names
-                                         .get(2)
-                                         .expect("Could not read game leader")),
-            assists_leader_value: *leader_values
-                .get(2)
-                .expect("Could not read game leader"),
-            color_value: TEAM_COLORS[&name[..]]
+            period_scores,
+            points_leader,
+            points_leader_value,
+            rebounds_leader,
+            rebounds_leader_value,
+            assists_leader,
+            assists_leader_value,
+            ..Team::new(name)
+        };
+    }
+
+    /// The name of the team in the given style, or as scraped for a team the registry doesn't know
+    pub fn display_name(&self, style: NameStyle) -> &str {
+        match self.info {
+            Some(info) => info.name(style),
+            None => &self.name,
         }
     }
+
+    /// Returns the game leaders of the team as (category, player name, value): points, rebounds
+    /// and assists, in that order. The name is empty for a leader the page doesn't list (yet).
+    pub fn leaders(&self) -> [(&'static str, &str, u32); 3] {
+        [
+            ("PTS", &self.points_leader, self.points_leader_value),
+            ("REB", &self.rebounds_leader, self.rebounds_leader_value),
+            ("AST", &self.assists_leader, self.assists_leader_value),
+        ]
+    }
 }
 
+/// Serializes what the registry knows about a team, or null for a team it doesn't know:
+///
+/// ```json
+/// {"abbreviation": "LAL", "full_name": "Los Angeles Lakers", "conference": "West", "division": "Pacific"}
+/// ```
+fn serialize_team_info<S: Serializer>(info: &Option<&'static TeamInfo>, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct TeamInfoJson {
+        abbreviation: &'static str,
+        full_name: &'static str,
+        conference: Conference,
+        division: Division,
+    }
+    return info
+        .map(|info| TeamInfoJson {
+            abbreviation: info.abbreviation,
+            full_name: info.full_name,
+            conference: info.conference(),
+            division: info.division,
+        })
+        .serialize(serializer);
+}
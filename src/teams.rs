@@ -0,0 +1,520 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The conferences of the league
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Conference {
+    East,
+    West,
+}
+
+/// The divisions of the league, 5 teams each
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Division {
+    Atlantic,
+    Central,
+    Southeast,
+    Northwest,
+    Pacific,
+    Southwest,
+}
+
+impl Division {
+    pub fn conference(&self) -> Conference {
+        match self {
+            Division::Atlantic | Division::Central | Division::Southeast => Conference::East,
+            Division::Northwest | Division::Pacific | Division::Southwest => Conference::West,
+        }
+    }
+}
+
+impl fmt::Display for Conference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl fmt::Display for Division {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Everything known about an NBA team
+#[derive(Debug)]
+pub struct TeamInfo {
+    /// Three letter abbreviation, e.g. "LAL"
+    pub abbreviation: &'static str,
+    /// e.g. "Los Angeles"
+    pub city: &'static str,
+    /// e.g. "Lakers"
+    pub nickname: &'static str,
+    /// The name used on scoreboards, the city unless two teams share it: "Boston", "LA Lakers"
+    pub short_name: &'static str,
+    /// e.g. "Los Angeles Lakers"
+    pub full_name: &'static str,
+    pub division: Division,
+    /// Main color of the team, the background of its name
+    pub primary_color: (u8, u8, u8),
+    /// Second color of the team, for the edges of its badge
+    pub secondary_color: (u8, u8, u8),
+    /// Other names the team goes by on scoreboards and in conversation
+    pub aliases: &'static [&'static str],
+}
+
+impl TeamInfo {
+    pub fn conference(&self) -> Conference {
+        self.division.conference()
+    }
+
+    /// The name of the team in the given style
+    pub fn name(&self, style: NameStyle) -> &'static str {
+        match style {
+            NameStyle::Abbreviation => self.abbreviation,
+            NameStyle::Short => self.short_name,
+            NameStyle::Full => self.full_name,
+        }
+    }
+}
+
+/// How team names are written on the scoreboard
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NameStyle {
+    /// "LAL"
+    #[clap(name = "abbr")]
+    Abbreviation,
+    /// "LA Lakers"
+    #[default]
+    Short,
+    /// "Los Angeles Lakers"
+    Full,
+}
+
+impl NameStyle {
+    /// Width of the column of team names in this style, wide enough for every team
+    pub fn width(&self) -> usize {
+        match self {
+            NameStyle::Abbreviation | NameStyle::Short => 16,
+            NameStyle::Full => 24,
+        }
+    }
+}
+
+/// Colors of a team missing from the registry (All-Star teams, international opponents, ...)
+pub const UNKNOWN_TEAM_COLORS: ((u8, u8, u8), (u8, u8, u8)) = ((85, 85, 85), (170, 170, 170));
+
+/// Finds a team by any of its names: abbreviation, short name, nickname, full name or one of its
+/// aliases, ignoring case. A city only matches if a single team plays there ("Los Angeles" is
+/// ambiguous, "Boston" is not). Returns None for a team the registry doesn't know.
+///
+/// # Examples
+///
+/// ```
+/// let lakers = find_team("LA Lakers").unwrap();
+/// assert_eq!(lakers.abbreviation, "LAL");
+/// assert!(find_team("Team LeBron").is_none());
+/// ```
+pub fn find_team(name: &str) -> Option<&'static TeamInfo> {
+    let name = name.trim().to_lowercase();
+    let matches = |candidate: &str| candidate.to_lowercase() == name;
+    let by_name = TEAMS.iter().find(|team| {
+        [team.abbreviation, team.short_name, team.nickname, team.full_name]
+            .iter()
+            .chain(team.aliases.iter())
+            .any(|candidate| matches(candidate))
+    });
+    if by_name.is_some() {
+        return by_name;
+    }
+    let mut by_city = TEAMS.iter().filter(|team| matches(team.city));
+    return match (by_city.next(), by_city.next()) {
+        (Some(team), None) => Some(team),
+        _ => None,
+    };
+}
+
+/// Finds a team given on the command line, see `find_team` for the names it goes by
+pub fn parse_team(name: &str) -> Result<&'static TeamInfo, String> {
+    return find_team(name).ok_or_else(|| format!("unknown team '{}'", name));
+}
+
+/// Every team of the league
+pub static TEAMS: [TeamInfo; 30] = [
+    TeamInfo {
+        abbreviation: "ATL",
+        city: "Atlanta",
+        nickname: "Hawks",
+        short_name: "Atlanta",
+        full_name: "Atlanta Hawks",
+        division: Division::Southeast,
+        primary_color: (225, 68, 52),
+        secondary_color: (193, 211, 47),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "BOS",
+        city: "Boston",
+        nickname: "Celtics",
+        short_name: "Boston",
+        full_name: "Boston Celtics",
+        division: Division::Atlantic,
+        primary_color: (0, 122, 51),
+        secondary_color: (186, 150, 83),
+        aliases: &["Celts"],
+    },
+    TeamInfo {
+        abbreviation: "BKN",
+        city: "Brooklyn",
+        nickname: "Nets",
+        short_name: "Brooklyn",
+        full_name: "Brooklyn Nets",
+        division: Division::Atlantic,
+        primary_color: (0, 0, 0),
+        secondary_color: (255, 255, 255),
+        aliases: &["BRK"],
+    },
+    TeamInfo {
+        abbreviation: "CHA",
+        city: "Charlotte",
+        nickname: "Hornets",
+        short_name: "Charlotte",
+        full_name: "Charlotte Hornets",
+        division: Division::Southeast,
+        primary_color: (29, 17, 96),
+        secondary_color: (0, 120, 140),
+        aliases: &["CHO"],
+    },
+    TeamInfo {
+        abbreviation: "CHI",
+        city: "Chicago",
+        nickname: "Bulls",
+        short_name: "Chicago",
+        full_name: "Chicago Bulls",
+        division: Division::Central,
+        primary_color: (206, 17, 65),
+        secondary_color: (6, 25, 34),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "CLE",
+        city: "Cleveland",
+        nickname: "Cavaliers",
+        short_name: "Cleveland",
+        full_name: "Cleveland Cavaliers",
+        division: Division::Central,
+        primary_color: (134, 0, 56),
+        secondary_color: (253, 187, 48),
+        aliases: &["Cavs"],
+    },
+    TeamInfo {
+        abbreviation: "DAL",
+        city: "Dallas",
+        nickname: "Mavericks",
+        short_name: "Dallas",
+        full_name: "Dallas Mavericks",
+        division: Division::Southwest,
+        primary_color: (0, 83, 188),
+        secondary_color: (0, 43, 94),
+        aliases: &["Mavs"],
+    },
+    TeamInfo {
+        abbreviation: "DEN",
+        city: "Denver",
+        nickname: "Nuggets",
+        short_name: "Denver",
+        full_name: "Denver Nuggets",
+        division: Division::Northwest,
+        primary_color: (13, 34, 64),
+        secondary_color: (254, 197, 36),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "DET",
+        city: "Detroit",
+        nickname: "Pistons",
+        short_name: "Detroit",
+        full_name: "Detroit Pistons",
+        division: Division::Central,
+        primary_color: (29, 66, 138),
+        secondary_color: (200, 16, 46),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "GSW",
+        city: "Golden State",
+        nickname: "Warriors",
+        short_name: "Golden State",
+        full_name: "Golden State Warriors",
+        division: Division::Pacific,
+        primary_color: (29, 66, 138),
+        secondary_color: (255, 199, 44),
+        aliases: &["GS", "Dubs"],
+    },
+    TeamInfo {
+        abbreviation: "HOU",
+        city: "Houston",
+        nickname: "Rockets",
+        short_name: "Houston",
+        full_name: "Houston Rockets",
+        division: Division::Southwest,
+        primary_color: (206, 17, 65),
+        secondary_color: (196, 206, 211),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "IND",
+        city: "Indiana",
+        nickname: "Pacers",
+        short_name: "Indiana",
+        full_name: "Indiana Pacers",
+        division: Division::Central,
+        primary_color: (0, 45, 98),
+        secondary_color: (253, 187, 48),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "LAC",
+        city: "Los Angeles",
+        nickname: "Clippers",
+        short_name: "LA Clippers",
+        full_name: "Los Angeles Clippers",
+        division: Division::Pacific,
+        primary_color: (200, 16, 46),
+        secondary_color: (29, 66, 148),
+        aliases: &["L.A. Clippers", "Clips"],
+    },
+    TeamInfo {
+        abbreviation: "LAL",
+        city: "Los Angeles",
+        nickname: "Lakers",
+        short_name: "LA Lakers",
+        full_name: "Los Angeles Lakers",
+        division: Division::Pacific,
+        primary_color: (85, 37, 130),
+        secondary_color: (253, 185, 39),
+        aliases: &["L.A. Lakers"],
+    },
+    TeamInfo {
+        abbreviation: "MEM",
+        city: "Memphis",
+        nickname: "Grizzlies",
+        short_name: "Memphis",
+        full_name: "Memphis Grizzlies",
+        division: Division::Southwest,
+        primary_color: (93, 118, 169),
+        secondary_color: (18, 23, 63),
+        aliases: &["Grizz"],
+    },
+    TeamInfo {
+        abbreviation: "MIA",
+        city: "Miami",
+        nickname: "Heat",
+        short_name: "Miami",
+        full_name: "Miami Heat",
+        division: Division::Southeast,
+        primary_color: (152, 0, 46),
+        secondary_color: (249, 160, 27),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "MIL",
+        city: "Milwaukee",
+        nickname: "Bucks",
+        short_name: "Milwaukee",
+        full_name: "Milwaukee Bucks",
+        division: Division::Central,
+        primary_color: (0, 71, 27),
+        secondary_color: (238, 225, 198),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "MIN",
+        city: "Minnesota",
+        nickname: "Timberwolves",
+        short_name: "Minnesota",
+        full_name: "Minnesota Timberwolves",
+        division: Division::Northwest,
+        primary_color: (12, 35, 64),
+        secondary_color: (120, 190, 32),
+        aliases: &["Wolves"],
+    },
+    TeamInfo {
+        abbreviation: "NOP",
+        city: "New Orleans",
+        nickname: "Pelicans",
+        short_name: "New Orleans",
+        full_name: "New Orleans Pelicans",
+        division: Division::Southwest,
+        primary_color: (0, 22, 65),
+        secondary_color: (180, 151, 90),
+        aliases: &["NO", "NOR", "Pels"],
+    },
+    TeamInfo {
+        abbreviation: "NYK",
+        city: "New York",
+        nickname: "Knicks",
+        short_name: "New York",
+        full_name: "New York Knicks",
+        division: Division::Atlantic,
+        primary_color: (0, 107, 182),
+        secondary_color: (245, 132, 38),
+        aliases: &["NY"],
+    },
+    TeamInfo {
+        abbreviation: "OKC",
+        city: "Oklahoma City",
+        nickname: "Thunder",
+        short_name: "Oklahoma City",
+        full_name: "Oklahoma City Thunder",
+        division: Division::Northwest,
+        primary_color: (0, 125, 195),
+        secondary_color: (239, 59, 36),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "ORL",
+        city: "Orlando",
+        nickname: "Magic",
+        short_name: "Orlando",
+        full_name: "Orlando Magic",
+        division: Division::Southeast,
+        primary_color: (0, 125, 197),
+        secondary_color: (196, 206, 211),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "PHI",
+        city: "Philadelphia",
+        nickname: "76ers",
+        short_name: "Philadelphia",
+        full_name: "Philadelphia 76ers",
+        division: Division::Atlantic,
+        primary_color: (0, 107, 182),
+        secondary_color: (237, 23, 76),
+        aliases: &["Sixers", "Philly"],
+    },
+    TeamInfo {
+        abbreviation: "PHX",
+        city: "Phoenix",
+        nickname: "Suns",
+        short_name: "Phoenix",
+        full_name: "Phoenix Suns",
+        division: Division::Pacific,
+        primary_color: (229, 95, 32),
+        secondary_color: (29, 17, 96),
+        aliases: &["PHO"],
+    },
+    TeamInfo {
+        abbreviation: "POR",
+        city: "Portland",
+        nickname: "Trail Blazers",
+        short_name: "Portland",
+        full_name: "Portland Trail Blazers",
+        division: Division::Northwest,
+        primary_color: (224, 58, 62),
+        secondary_color: (0, 0, 0),
+        aliases: &["Blazers"],
+    },
+    TeamInfo {
+        abbreviation: "SAC",
+        city: "Sacramento",
+        nickname: "Kings",
+        short_name: "Sacramento",
+        full_name: "Sacramento Kings",
+        division: Division::Pacific,
+        primary_color: (91, 43, 130),
+        secondary_color: (99, 113, 122),
+        aliases: &[],
+    },
+    TeamInfo {
+        abbreviation: "SAS",
+        city: "San Antonio",
+        nickname: "Spurs",
+        short_name: "San Antonio",
+        full_name: "San Antonio Spurs",
+        division: Division::Southwest,
+        primary_color: (6, 25, 34),
+        secondary_color: (196, 206, 211),
+        aliases: &["SA"],
+    },
+    TeamInfo {
+        abbreviation: "TOR",
+        city: "Toronto",
+        nickname: "Raptors",
+        short_name: "Toronto",
+        full_name: "Toronto Raptors",
+        division: Division::Atlantic,
+        primary_color: (206, 17, 65),
+        secondary_color: (6, 25, 34),
+        aliases: &["Raps"],
+    },
+    TeamInfo {
+        abbreviation: "UTA",
+        city: "Utah",
+        nickname: "Jazz",
+        short_name: "Utah",
+        full_name: "Utah Jazz",
+        division: Division::Northwest,
+        primary_color: (0, 43, 92),
+        secondary_color: (249, 160, 27),
+        aliases: &["UTAH"],
+    },
+    TeamInfo {
+        abbreviation: "WAS",
+        city: "Washington",
+        nickname: "Wizards",
+        short_name: "Washington",
+        full_name: "Washington Wizards",
+        division: Division::Southeast,
+        primary_color: (0, 43, 92),
+        secondary_color: (227, 24, 55),
+        aliases: &["WSH", "Wiz"],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abbreviation(name: &str) -> Option<&'static str> {
+        return find_team(name).map(|team| team.abbreviation);
+    }
+
+    #[test]
+    fn finds_teams_by_any_name() {
+        assert_eq!(abbreviation("LAL"), Some("LAL"));
+        assert_eq!(abbreviation("lal"), Some("LAL"));
+        assert_eq!(abbreviation("LA Lakers"), Some("LAL"));
+        assert_eq!(abbreviation("Lakers"), Some("LAL"));
+        assert_eq!(abbreviation("Los Angeles Lakers"), Some("LAL"));
+        assert_eq!(abbreviation("L.A. Clippers"), Some("LAC"));
+        assert_eq!(abbreviation("  sixers "), Some("PHI"));
+    }
+
+    #[test]
+    fn finds_teams_by_city_when_only_one_plays_there() {
+        assert_eq!(abbreviation("Boston"), Some("BOS"));
+        assert_eq!(abbreviation("new york"), Some("NYK"));
+        // the Lakers and the Clippers both play in Los Angeles
+        assert_eq!(abbreviation("Los Angeles"), None);
+    }
+
+    #[test]
+    fn knows_only_league_teams() {
+        assert_eq!(abbreviation("Team LeBron"), None);
+        assert_eq!(abbreviation(""), None);
+        assert_eq!(parse_team("Lakerz").unwrap_err(), "unknown team 'Lakerz'");
+    }
+
+    #[test]
+    fn finds_every_team_by_each_of_its_names() {
+        for team in TEAMS.iter() {
+            let names = [team.abbreviation, team.short_name, team.nickname, team.full_name];
+            for name in names.iter().chain(team.aliases.iter()) {
+                assert_eq!(abbreviation(name), Some(team.abbreviation), "{}", name);
+            }
+        }
+    }
+}
@@ -1,9 +1,69 @@
-// Used strictly for finding the times of when games start
-pub enum TimeZone {
-    // Defines different timezones (US only for now)
-    Pacific,
-    Mountain,
-    Central,
-    Eastern,
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+
+/// The time zone the scoreboard page gives start times in
+const EASTERN: Tz = chrono_tz::America::New_York;
+
+// the zone start times are shown in, detected on first use if never set
+static TIME_ZONE: OnceLock<Tz> = OnceLock::new();
+
+/// Sets the time zone start times are shown in for the rest of the program. None detects it from
+/// the system. Only the first call has any effect.
+pub fn set_time_zone(zone: Option<Tz>) {
+    let _ = TIME_ZONE.set(zone.unwrap_or_else(detect_time_zone));
+}
+
+/// The time zone start times are shown in
+pub fn time_zone() -> Tz {
+    return *TIME_ZONE.get_or_init(detect_time_zone);
 }
 
+/// Finds the time zone of the system: the TZ environment variable if it names an IANA zone
+/// ("Europe/London"), else the zone the system is set to. Falls back to US Eastern time, the time
+/// zone of the scoreboard page.
+pub fn detect_time_zone() -> Tz {
+    // TZ may start with a colon (":Europe/London")
+    let from_env = std::env::var("TZ")
+        .ok()
+        .and_then(|name| Tz::from_str(name.trim_start_matches(':')).ok());
+    if let Some(zone) = from_env {
+        return zone;
+    }
+    return iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| Tz::from_str(&name).ok())
+        .unwrap_or(EASTERN);
+}
+
+/// Reads a start time as the scoreboard page writes it in US Eastern time ("7:30 PM ET") on the day
+/// of the game. Daylight saving time is taken into account.
+///
+/// # Arguments
+///
+/// * `date` - The day of the game, in US Eastern time
+/// * `text` - The start time from the page
+///
+/// # Examples
+///
+/// ```
+/// let start = parse_eastern_start(date, "7:30 PM ET").unwrap();
+/// ```
+pub fn parse_eastern_start(date: NaiveDate, text: &str) -> Option<DateTime<Tz>> {
+    let time = text.trim().trim_end_matches("ET").trim();
+    let time = NaiveTime::parse_from_str(time, "%I:%M %p").ok()?;
+    return EASTERN.from_local_datetime(&date.and_time(time)).earliest();
+}
+
+/// Writes a start time in the time zone the program is set to, with the zone's abbreviation
+/// ("12:30 AM BST"). The weekday is added when it falls on another day than `date`, the day of the
+/// scoreboard ("12:30 AM BST Sun").
+pub fn format_start(start: DateTime<Tz>, date: NaiveDate) -> String {
+    let local = start.with_timezone(&time_zone());
+    if local.date_naive() == date {
+        return local.format("%-I:%M %p %Z").to_string();
+    }
+    return local.format("%-I:%M %p %Z %a").to_string();
+}